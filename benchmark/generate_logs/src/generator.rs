@@ -0,0 +1,332 @@
+//! Reusable log-line generation, extracted from `main` so it can be driven
+//! as a library instead of only through the `generate_logs` CLI. Every
+//! fallible step (unknown format, empty data pool, serialization) returns a
+//! [`GenError`] instead of panicking.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::prelude::*;
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Debug)]
+pub enum GenError {
+    /// An unrecognized `--format` value was requested.
+    UnknownFormat(String),
+    /// A data pool (e.g. `IPS`, `PATHS`) was empty when a selection was
+    /// attempted. The pools are all non-empty constants, so this should
+    /// never happen in practice, but selecting from them still returns a
+    /// `Result` rather than unwrapping.
+    EmptyPool(&'static str),
+    Json(serde_json::Error),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for GenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenError::UnknownFormat(s) => write!(f, "Unknown format: {}", s),
+            GenError::EmptyPool(name) => write!(f, "Data pool '{}' is empty", name),
+            GenError::Json(e) => write!(f, "Failed to serialize log line: {}", e),
+            GenError::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GenError {}
+
+impl From<serde_json::Error> for GenError {
+    fn from(e: serde_json::Error) -> Self {
+        GenError::Json(e)
+    }
+}
+
+impl From<std::io::Error> for GenError {
+    fn from(e: std::io::Error) -> Self {
+        GenError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Apache,
+    Json,
+    Syslog,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = GenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "apache" => Ok(LogFormat::Apache),
+            "json" => Ok(LogFormat::Json),
+            "syslog" => Ok(LogFormat::Syslog),
+            _ => Err(GenError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Pick a random element from `pool`, failing instead of panicking if it's
+/// empty.
+fn pick<'a, T>(rng: &mut impl Rng, pool: &'a [T], name: &'static str) -> Result<&'a T, GenError> {
+    pool.choose(rng).ok_or(GenError::EmptyPool(name))
+}
+
+// Realistic data pools
+const IPS: &[&str] = &[
+    "192.168.1.100", "192.168.1.101", "192.168.1.102", "192.168.1.103",
+    "10.0.0.50", "10.0.0.51", "10.0.0.52", "10.0.0.53",
+    "172.16.0.10", "172.16.0.11", "172.16.0.12",
+    "203.0.113.50", "203.0.113.51", // Suspicious IPs for attacks
+];
+
+const PATHS: &[&str] = &[
+    "/", "/index.html", "/about", "/contact", "/products", "/api/users",
+    "/api/products", "/api/orders", "/api/checkout", "/api/login", "/api/logout",
+    "/static/css/style.css", "/static/js/app.js", "/static/img/logo.png",
+    "/admin", "/admin/dashboard", "/health", "/metrics", "/favicon.ico",
+];
+
+const METHODS: &[&str] = &["GET", "GET", "GET", "GET", "POST", "PUT", "DELETE"];
+
+const USER_AGENTS: &[&str] = &[
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36",
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36",
+    "Mozilla/5.0 (iPhone; CPU iPhone OS 14_0 like Mac OS X)",
+    "curl/7.68.0",
+    "PostmanRuntime/7.28.4",
+    "python-requests/2.25.1",
+];
+
+const SERVICES: &[&str] = &[
+    "api-gateway", "user-service", "payment-service", "order-service",
+    "notification-service", "cache-service", "auth-service",
+];
+
+const LOG_LEVELS: &[&str] = &["DEBUG", "INFO", "INFO", "INFO", "WARN", "ERROR"];
+
+const ERROR_MESSAGES: &[&str] = &[
+    "Database connection timeout",
+    "Connection refused",
+    "Out of memory",
+    "Disk space low",
+    "Authentication failed",
+    "Rate limit exceeded",
+    "Service unavailable",
+    "Invalid request",
+];
+
+const HOSTNAMES: &[&str] = &[
+    "webserver01", "webserver02", "appserver01", "appserver02",
+    "dbserver01", "cacheserver01", "loadbalancer",
+];
+
+const PROCESSES: &[&str] = &[
+    "nginx", "sshd", "mysqld", "redis-server", "app", "haproxy", "kernel",
+];
+
+#[derive(Serialize)]
+struct JsonLog {
+    timestamp: String,
+    level: String,
+    service: String,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+}
+
+/// Generate one log line in `format`, dispatching to the per-format
+/// builder below.
+pub fn generate_line(
+    format: LogFormat,
+    rng: &mut impl Rng,
+    timestamp: DateTime<Utc>,
+    is_error: bool,
+) -> Result<String, GenError> {
+    match format {
+        LogFormat::Apache => generate_apache_log(rng, timestamp, is_error),
+        LogFormat::Json => generate_json_log(rng, timestamp, is_error),
+        LogFormat::Syslog => generate_syslog(rng, timestamp, is_error),
+    }
+}
+
+fn generate_apache_log(rng: &mut impl Rng, timestamp: DateTime<Utc>, is_error: bool) -> Result<String, GenError> {
+    let ip = pick(rng, IPS, "IPS")?;
+    let method = pick(rng, METHODS, "METHODS")?;
+    let path = pick(rng, PATHS, "PATHS")?;
+    let user_agent = pick(rng, USER_AGENTS, "USER_AGENTS")?;
+
+    let status = if is_error {
+        *pick(rng, &[400, 401, 403, 404, 500, 502, 503], "error statuses")?
+    } else {
+        *pick(rng, &[200, 200, 200, 200, 201, 204, 301, 304], "success statuses")?
+    };
+
+    let size = if status >= 400 { rng.gen_range(50..200) } else { rng.gen_range(500..50000) };
+    let ts = timestamp.format("%d/%b/%Y:%H:%M:%S %z");
+
+    Ok(format!(
+        "{} - - [{}] \"{} {} HTTP/1.1\" {} {} \"-\" \"{}\"",
+        ip, ts, method, path, status, size, user_agent
+    ))
+}
+
+fn generate_json_log(rng: &mut impl Rng, timestamp: DateTime<Utc>, is_error: bool) -> Result<String, GenError> {
+    let service = pick(rng, SERVICES, "SERVICES")?;
+    let level = if is_error {
+        "ERROR"
+    } else {
+        pick(rng, LOG_LEVELS, "LOG_LEVELS")?
+    };
+
+    let (message, error, duration, path, status) = if is_error {
+        let err_msg = pick(rng, ERROR_MESSAGES, "ERROR_MESSAGES")?;
+        (err_msg.to_string(), Some(err_msg.to_string()), None, None, None)
+    } else {
+        let path = pick(rng, PATHS, "PATHS")?;
+        (
+            format!("Request processed for {}", path),
+            None,
+            Some(rng.gen_range(10..500)),
+            Some(path.to_string()),
+            Some(*pick(rng, &[200u16, 200, 200, 201, 204], "success statuses")?),
+        )
+    };
+
+    let log = JsonLog {
+        timestamp: timestamp.to_rfc3339(),
+        level: level.to_string(),
+        service: service.to_string(),
+        message,
+        error,
+        duration_ms: duration,
+        path,
+        status,
+    };
+
+    Ok(serde_json::to_string(&log)?)
+}
+
+fn generate_syslog(rng: &mut impl Rng, timestamp: DateTime<Utc>, is_error: bool) -> Result<String, GenError> {
+    let hostname = pick(rng, HOSTNAMES, "HOSTNAMES")?;
+    let process = pick(rng, PROCESSES, "PROCESSES")?;
+    let pid = rng.gen_range(1000..50000);
+    let ts = timestamp.format("%b %d %H:%M:%S");
+
+    let message = if is_error {
+        let err = pick(rng, ERROR_MESSAGES, "ERROR_MESSAGES")?;
+        format!("ERROR {}", err)
+    } else {
+        match *process {
+            "sshd" => format!("Accepted publickey for user{} from {} port {}",
+                rng.gen_range(1..10), pick(rng, IPS, "IPS")?, rng.gen_range(40000..60000)),
+            "nginx" => format!("*{} upstream response time: {}ms",
+                rng.gen_range(1000..9999), rng.gen_range(10..500)),
+            "mysqld" => format!("Query executed in {}ms", rng.gen_range(1..100)),
+            _ => "Operation completed successfully".to_string(),
+        }
+    };
+
+    Ok(format!("{} {} {}[{}]: {}", ts, hostname, process, pid, message))
+}
+
+/// Options for a bulk, non-interactive generation run (no progress
+/// reporting); see [`generate_into`].
+pub struct GenerateOptions {
+    pub format: LogFormat,
+    pub lines: usize,
+    pub error_rate: f64,
+    pub start_time: DateTime<Utc>,
+}
+
+/// Summary of a completed [`generate_into`] run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stats {
+    pub lines_written: usize,
+    pub bytes_written: u64,
+}
+
+/// Stream `opts.lines` generated log lines into `writer`, one per line.
+/// Callers that need progress feedback (e.g. the CLI's progress bar) should
+/// call [`generate_line`] directly in their own loop instead; this is the
+/// simpler entry point for library consumers that just want the lines.
+pub fn generate_into<W: Write>(
+    writer: &mut W,
+    opts: &GenerateOptions,
+    rng: &mut impl Rng,
+) -> Result<Stats, GenError> {
+    let mut stats = Stats::default();
+
+    for i in 0..opts.lines {
+        let timestamp = opts.start_time + Duration::milliseconds((i as i64) * 50 + rng.gen_range(0..50));
+        let is_error = rng.r#gen::<f64>() < opts.error_rate;
+        let line = generate_line(opts.format, rng, timestamp, is_error)?;
+
+        writeln!(writer, "{}", line)?;
+        stats.lines_written += 1;
+        stats.bytes_written += line.len() as u64 + 1;
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_line_apache_never_panics() {
+        let mut rng = rand::thread_rng();
+        let ts = Utc::now();
+        let line = generate_line(LogFormat::Apache, &mut rng, ts, false).unwrap();
+        assert!(line.contains("HTTP/1.1"));
+    }
+
+    #[test]
+    fn test_generate_line_json_is_valid_json() {
+        let mut rng = rand::thread_rng();
+        let ts = Utc::now();
+        let line = generate_line(LogFormat::Json, &mut rng, ts, true).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["level"], "ERROR");
+    }
+
+    #[test]
+    fn test_generate_line_syslog_contains_hostname_and_process() {
+        let mut rng = rand::thread_rng();
+        let ts = Utc::now();
+        let line = generate_line(LogFormat::Syslog, &mut rng, ts, false).unwrap();
+        assert!(line.contains('['));
+        assert!(line.contains("]: "));
+        assert!(HOSTNAMES.iter().any(|h| line.contains(&format!(" {} ", h))));
+        assert!(PROCESSES.iter().any(|p| line.contains(&format!("{}[", p))));
+    }
+
+    #[test]
+    fn test_format_from_str_rejects_unknown() {
+        assert!("apache".parse::<LogFormat>().is_ok());
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn test_generate_into_reports_accurate_stats() {
+        let mut rng = rand::thread_rng();
+        let opts = GenerateOptions {
+            format: LogFormat::Syslog,
+            lines: 10,
+            error_rate: 0.5,
+            start_time: Utc::now(),
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        let stats = generate_into(&mut buf, &opts, &mut rng).unwrap();
+        assert_eq!(stats.lines_written, 10);
+        assert_eq!(stats.bytes_written, buf.len() as u64);
+    }
+}
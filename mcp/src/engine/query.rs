@@ -1,4 +1,5 @@
 use polars::prelude::*;
+use polars::sql::SQLContext;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -36,6 +37,30 @@ impl QueryBuilder {
         self
     }
 
+    /// OR together multiple regex patterns on a column, keeping any row
+    /// that matches at least one.
+    pub fn filter_regex_any(mut self, column: &str, patterns: &[String]) -> Self {
+        if let Some((first, rest)) = patterns.split_first() {
+            let mut expr = col(column).str().contains(lit(first.clone()), false);
+            for pattern in rest {
+                expr = expr.or(col(column).str().contains(lit(pattern.clone()), false));
+            }
+            self.lf = self.lf.filter(expr);
+        }
+        self
+    }
+
+    /// Add one boolean column per pattern (named `pattern_0`, `pattern_1`,
+    /// ...) marking whether it matched `column` in that row, so a
+    /// multi-pattern search can report which alternative(s) fired.
+    pub fn annotate_regex_matches(mut self, column: &str, patterns: &[String]) -> Self {
+        for (i, pattern) in patterns.iter().enumerate() {
+            let expr = col(column).str().contains(lit(pattern.clone()), false).alias(format!("pattern_{}", i));
+            self.lf = self.lf.with_column(expr);
+        }
+        self
+    }
+
     /// Filter by status code (supports ranges like ">=400", "4xx", "500")
     pub fn filter_status(mut self, status_filter: &str) -> Self {
         let expr = parse_status_filter(status_filter);
@@ -45,31 +70,171 @@ impl QueryBuilder {
         self
     }
 
-    /// Filter by time range
-    pub fn filter_time_range(mut self, column: &str, start: Option<&str>, end: Option<&str>) -> Self {
+    /// Filter by time range. `column` and the `start`/`end` bounds are
+    /// parsed into a real temporal value before comparing, rather than
+    /// compared as raw strings (which silently gives wrong results for any
+    /// non-ISO-lexicographic timestamp format). `format` is a strptime-style
+    /// format string for the column's native layout (e.g.
+    /// `%d/%b/%Y:%H:%M:%S %z` for Apache/CLF logs); pass `None` to let
+    /// Polars infer an ISO8601-ish format instead. The `start`/`end` bound
+    /// strings are always parsed against an inferred ISO8601-ish format,
+    /// independent of `format` — callers document that bounds are supplied
+    /// in ISO form regardless of the column's native layout, so a bound must
+    /// not be forced through the column's (possibly very different) format.
+    /// When `column` is already an integer epoch (seconds or millis), the
+    /// bounds are parsed and compared numerically instead of as a datetime.
+    pub fn filter_time_range(mut self, column: &str, start: Option<&str>, end: Option<&str>, format: Option<&str>) -> Result<Self, QueryError> {
+        if start.is_none() && end.is_none() {
+            return Ok(self);
+        }
+
+        let schema = self.lf.schema()
+            .map_err(|e| QueryError::InvalidQuery(format!("Unable to resolve schema: {}", e)))?;
+        let dtype = schema.get(column)
+            .ok_or_else(|| QueryError::InvalidQuery(format!("Unknown column: {}", column)))?;
+
+        if dtype.is_integer() {
+            if let Some(start_time) = start {
+                let val: i64 = start_time.trim().parse()
+                    .map_err(|_| QueryError::InvalidQuery(format!("Invalid numeric time bound: {}", start_time)))?;
+                self.lf = self.lf.filter(col(column).gt_eq(lit(val)));
+            }
+            if let Some(end_time) = end {
+                let val: i64 = end_time.trim().parse()
+                    .map_err(|_| QueryError::InvalidQuery(format!("Invalid numeric time bound: {}", end_time)))?;
+                self.lf = self.lf.filter(col(column).lt_eq(lit(val)));
+            }
+            return Ok(self);
+        }
+
+        let column_opts = StrptimeOptions {
+            format: format.map(|f| f.into()),
+            strict: true,
+            exact: true,
+            cache: true,
+        };
+        // Bounds are always ISO8601-ish, regardless of the column's native
+        // format (e.g. `%d/%b/%Y:%H:%M:%S %z` for Apache/CLF) — forcing a
+        // bound through the column's format would reject the ISO strings
+        // this API documents bounds as accepting.
+        let bound_opts = StrptimeOptions {
+            format: None,
+            strict: true,
+            exact: true,
+            cache: true,
+        };
+        let datetime_dtype = DataType::Datetime(TimeUnit::Milliseconds, None);
+        let parse_column = || col(column).str().strptime(datetime_dtype.clone(), column_opts.clone(), lit("raise"));
+        let parse_bound = |s: &str| lit(s.to_string()).str().strptime(datetime_dtype.clone(), bound_opts.clone(), lit("raise"));
+
         if let Some(start_time) = start {
-            self.lf = self.lf.filter(col(column).gt_eq(lit(start_time)));
+            self.lf = self.lf.filter(parse_column().gt_eq(parse_bound(start_time)));
         }
         if let Some(end_time) = end {
-            self.lf = self.lf.filter(col(column).lt_eq(lit(end_time)));
+            self.lf = self.lf.filter(parse_column().lt_eq(parse_bound(end_time)));
         }
-        self
+
+        Ok(self)
     }
 
     /// Group by a column
     pub fn group_by(self, column: &str) -> GroupByBuilder {
         GroupByBuilder {
             lf: self.lf,
-            group_col: column.to_string(),
+            group_cols: vec![column.to_string()],
         }
     }
 
+    /// Group by several columns at once, e.g. `&["status", "remote_host"]`
+    /// for "per status code, per host" buckets.
+    pub fn group_by_many(self, columns: &[&str]) -> GroupByBuilder {
+        GroupByBuilder {
+            lf: self.lf,
+            group_cols: columns.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    /// Group rows into fixed time windows over `time_col`, for "events per
+    /// minute/hour"-style rollups instead of grouping by a discrete column
+    /// value. `every` spaces window starts (e.g. "1h"); `period` is each
+    /// window's width (equal to `every` for tumbling windows, larger for
+    /// overlapping/sliding ones); `offset` shifts every window start;
+    /// `closed` controls which window boundary is inclusive. The first
+    /// window is anchored to the earliest timestamp in `time_col` rather
+    /// than a fixed epoch, so a datapoint sitting exactly on what would
+    /// otherwise be a boundary always lands in the first emitted window.
+    /// `format` is a strptime format string for parsing `time_col` (e.g.
+    /// `"%d/%b/%Y:%H:%M:%S %z"` for Apache/Nginx). When `time_col` is already
+    /// an integer epoch (seconds or millis), it's reinterpreted directly as a
+    /// millisecond-epoch datetime instead, matching how
+    /// [`Self::filter_time_range`] treats integer time columns. When
+    /// `format` is `None` (e.g. syslog's yearless BSD timestamps, which no
+    /// strptime format can fully describe), parsing is non-strict: rows
+    /// Polars can't infer a timestamp for become null buckets rather than
+    /// failing the whole query.
+    pub fn group_by_dynamic(mut self, time_col: &str, every: &str, period: &str, offset: &str, closed: WindowClosed, format: Option<&str>) -> Result<DynamicGroupByBuilder, QueryError> {
+        let schema = self.lf.schema()
+            .map_err(|e| QueryError::InvalidQuery(format!("Unable to resolve schema: {}", e)))?;
+        let dtype = schema.get(time_col)
+            .ok_or_else(|| QueryError::InvalidQuery(format!("Unknown column: {}", time_col)))?;
+
+        let datetime_expr = if dtype.is_integer() {
+            col(time_col).cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+        } else {
+            let strptime_opts = StrptimeOptions {
+                format: format.map(|f| f.into()),
+                strict: format.is_some(),
+                exact: true,
+                cache: true,
+            };
+            col(time_col)
+                .str()
+                .strptime(DataType::Datetime(TimeUnit::Milliseconds, None), strptime_opts, lit("raise"))
+        };
+
+        self.lf = self.lf.with_column(datetime_expr.alias(time_col));
+        self.lf = self.lf.sort([time_col], SortMultipleOptions::default());
+
+        let lgb = self.lf.group_by_dynamic(
+            col(time_col),
+            [],
+            DynamicGroupOptions {
+                every: Duration::parse(every),
+                period: Duration::parse(period),
+                offset: Duration::parse(offset),
+                closed_window: closed.to_polars(),
+                start_by: StartBy::DataPoint,
+                ..Default::default()
+            },
+        );
+
+        Ok(DynamicGroupByBuilder {
+            lgb,
+            time_col: time_col.to_string(),
+        })
+    }
+
     /// Sort by a column
     pub fn sort(mut self, column: &str, descending: bool) -> Self {
         self.lf = self.lf.sort([column], SortMultipleOptions::default().with_order_descending(descending));
         self
     }
 
+    /// Keep only the `n` "best" rows by `column` (highest if `descending`,
+    /// lowest otherwise) without materializing a full sort over every row —
+    /// Polars' query optimizer turns a sort immediately followed by a limit
+    /// into a bounded top-k selection (a heap of size `n`, O(rows·log n))
+    /// rather than sorting the whole frame, so this is the idiomatic way to
+    /// express "top 20 by X" instead of spelling out `sort().limit()`.
+    /// Ties at the `n`th boundary are broken by original row order, so
+    /// repeated runs over the same data are reproducible.
+    pub fn top_k(mut self, column: &str, n: u32, descending: bool) -> Self {
+        self.lf = self.lf
+            .sort([column], SortMultipleOptions::default().with_order_descending(descending).with_maintain_order(true))
+            .limit(n);
+        self
+    }
+
     /// Limit results
     pub fn limit(mut self, n: u32) -> Self {
         self.lf = self.lf.limit(n);
@@ -88,67 +253,232 @@ impl QueryBuilder {
         self.lf.collect().map_err(QueryError::from)
     }
 
+    /// Execute an arbitrary SQL `SELECT` against the frame built so far,
+    /// registered as `table_name`. Lets callers apply the fluent builder
+    /// methods as a pre-filter (status/text/time filters, column pruning)
+    /// and then hand off to polars-sql for joins, `GROUP BY ... HAVING`,
+    /// `CASE` expressions, and subqueries the builder itself can't express.
+    pub fn sql(self, table_name: &str, query: &str) -> Result<DataFrame, QueryError> {
+        run_sql(self.lf, table_name, query)
+    }
+
     /// Get the LazyFrame for further manipulation
     pub fn into_lazy(self) -> LazyFrame {
         self.lf
     }
 }
 
+/// Boundary inclusivity for a [`QueryBuilder::group_by_dynamic`] window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowClosed {
+    Left,
+    Right,
+    Both,
+    Neither,
+}
+
+impl WindowClosed {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "left" => Some(WindowClosed::Left),
+            "right" => Some(WindowClosed::Right),
+            "both" => Some(WindowClosed::Both),
+            "none" | "neither" => Some(WindowClosed::Neither),
+            _ => None,
+        }
+    }
+
+    fn to_polars(self) -> ClosedWindow {
+        match self {
+            WindowClosed::Left => ClosedWindow::Left,
+            WindowClosed::Right => ClosedWindow::Right,
+            WindowClosed::Both => ClosedWindow::Both,
+            WindowClosed::Neither => ClosedWindow::None,
+        }
+    }
+}
+
+/// One aggregate to compute per group in a [`GroupByBuilder::agg`] call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggKind {
+    /// Row count per group. The paired column name is only used to satisfy
+    /// the uniform `(AggKind, &str)` spec shape — any column works, and the
+    /// output is always aliased `count`.
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    UniqueCount,
+}
+
+impl AggKind {
+    fn expr(self, column: &str) -> Expr {
+        match self {
+            AggKind::Count => len(),
+            AggKind::Sum => col(column).sum(),
+            AggKind::Avg => col(column).mean(),
+            AggKind::Min => col(column).min(),
+            AggKind::Max => col(column).max(),
+            AggKind::UniqueCount => col(column).n_unique(),
+        }
+    }
+
+    fn alias(self, column: &str) -> String {
+        match self {
+            AggKind::Count => "count".to_string(),
+            AggKind::Sum => format!("{}_sum", column),
+            AggKind::Avg => format!("{}_avg", column),
+            AggKind::Min => format!("{}_min", column),
+            AggKind::Max => format!("{}_max", column),
+            AggKind::UniqueCount => format!("{}_unique_count", column),
+        }
+    }
+}
+
 pub struct GroupByBuilder {
     lf: LazyFrame,
-    group_col: String,
+    group_cols: Vec<String>,
 }
 
 impl GroupByBuilder {
+    fn group_exprs(&self) -> Vec<Expr> {
+        self.group_cols.iter().map(|c| col(c)).collect()
+    }
+
+    /// Compute several aggregates per group in a single grouped scan, e.g.
+    /// `&[(AggKind::Count, "status"), (AggKind::Avg, "bytes"), (AggKind::Max, "latency")]`
+    /// for "per group, give me count AND average bytes AND max latency" in
+    /// one pass instead of several. Each spec is aliased distinctly (e.g.
+    /// `bytes_avg`, `latency_max`); see [`AggKind::alias`].
+    pub fn agg(self, specs: &[(AggKind, &str)]) -> QueryBuilder {
+        let group_exprs = self.group_exprs();
+        let agg_exprs: Vec<Expr> = specs.iter()
+            .map(|(kind, column)| kind.expr(column).alias(kind.alias(column)))
+            .collect();
+        let lf = self.lf.group_by(group_exprs).agg(agg_exprs);
+        QueryBuilder { lf }
+    }
+
+    /// Re-select `group_cols` plus the single aggregate column produced by
+    /// `agg(&[(kind, column)])`, renamed from `kind`'s distinct alias (e.g.
+    /// `bytes_sum`) back to `output_name` — keeps the single-aggregate
+    /// helpers' historical output schema (`"sum"`, `"avg"`, ...) stable even
+    /// though they're now thin wrappers over [`GroupByBuilder::agg`].
+    fn agg_one(self, kind: AggKind, column: &str, output_name: &str) -> QueryBuilder {
+        let group_exprs = self.group_exprs();
+        let agg_alias = kind.alias(column);
+        let qb = self.agg(&[(kind, column)]);
+        let select_exprs: Vec<Expr> = group_exprs.into_iter()
+            .chain(std::iter::once(col(&agg_alias).alias(output_name)))
+            .collect();
+        QueryBuilder { lf: qb.lf.select(select_exprs) }
+    }
+
     /// Count occurrences per group
     pub fn count(self) -> QueryBuilder {
-        let lf = self.lf
-            .group_by([col(&self.group_col)])
-            .agg([col(&self.group_col).count().alias("count")])
-            .sort(["count"], SortMultipleOptions::default().with_order_descending(true));
-        QueryBuilder { lf }
+        let column = self.group_cols.first().cloned().unwrap_or_default();
+        let qb = self.agg_one(AggKind::Count, &column, "count");
+        QueryBuilder { lf: qb.lf.sort(["count"], SortMultipleOptions::default().with_order_descending(true)) }
     }
 
     /// Sum a column per group
     pub fn sum(self, column: &str) -> QueryBuilder {
-        let lf = self.lf
-            .group_by([col(&self.group_col)])
-            .agg([col(column).sum().alias("sum")])
-            .sort(["sum"], SortMultipleOptions::default().with_order_descending(true));
-        QueryBuilder { lf }
+        let qb = self.agg_one(AggKind::Sum, column, "sum");
+        QueryBuilder { lf: qb.lf.sort(["sum"], SortMultipleOptions::default().with_order_descending(true)) }
     }
 
     /// Average a column per group
     pub fn avg(self, column: &str) -> QueryBuilder {
-        let lf = self.lf
-            .group_by([col(&self.group_col)])
-            .agg([col(column).mean().alias("avg")])
-            .sort(["avg"], SortMultipleOptions::default().with_order_descending(true));
-        QueryBuilder { lf }
+        let qb = self.agg_one(AggKind::Avg, column, "avg");
+        QueryBuilder { lf: qb.lf.sort(["avg"], SortMultipleOptions::default().with_order_descending(true)) }
     }
 
     /// Min value per group
     pub fn min(self, column: &str) -> QueryBuilder {
-        let lf = self.lf
-            .group_by([col(&self.group_col)])
-            .agg([col(column).min().alias("min")]);
-        QueryBuilder { lf }
+        self.agg_one(AggKind::Min, column, "min")
     }
 
     /// Max value per group
     pub fn max(self, column: &str) -> QueryBuilder {
-        let lf = self.lf
-            .group_by([col(&self.group_col)])
-            .agg([col(column).max().alias("max")]);
-        QueryBuilder { lf }
+        self.agg_one(AggKind::Max, column, "max")
     }
 
     /// Count unique values per group
     pub fn unique_count(self, column: &str) -> QueryBuilder {
+        let qb = self.agg_one(AggKind::UniqueCount, column, "unique_count");
+        QueryBuilder { lf: qb.lf.sort(["unique_count"], SortMultipleOptions::default().with_order_descending(true)) }
+    }
+
+    /// Count occurrences per group, keeping only the `n` groups with the
+    /// highest count (e.g. "top 20 source IPs by request count") without a
+    /// full sort over every group. See [`QueryBuilder::top_k`].
+    pub fn top_k(self, n: u32) -> QueryBuilder {
+        let group_exprs = self.group_exprs();
         let lf = self.lf
-            .group_by([col(&self.group_col)])
-            .agg([col(column).n_unique().alias("unique_count")])
-            .sort(["unique_count"], SortMultipleOptions::default().with_order_descending(true));
+            .group_by(group_exprs)
+            .agg([len().alias("count")])
+            .sort(["count"], SortMultipleOptions::default().with_order_descending(true).with_maintain_order(true))
+            .limit(n);
+        QueryBuilder { lf }
+    }
+
+    /// Count occurrences per group, keeping only the `n` groups with the
+    /// lowest count. See [`QueryBuilder::top_k`].
+    pub fn bottom_k(self, n: u32) -> QueryBuilder {
+        let group_exprs = self.group_exprs();
+        let lf = self.lf
+            .group_by(group_exprs)
+            .agg([len().alias("count")])
+            .sort(["count"], SortMultipleOptions::default().with_order_descending(false).with_maintain_order(true))
+            .limit(n);
+        QueryBuilder { lf }
+    }
+}
+
+pub struct DynamicGroupByBuilder {
+    lgb: LazyGroupBy,
+    time_col: String,
+}
+
+impl DynamicGroupByBuilder {
+    /// Count rows per window
+    pub fn count(self) -> QueryBuilder {
+        let lf = self.lgb
+            .agg([col(&self.time_col).count().alias("count")])
+            .sort([&self.time_col], SortMultipleOptions::default());
+        QueryBuilder { lf }
+    }
+
+    /// Sum a column per window
+    pub fn sum(self, column: &str) -> QueryBuilder {
+        let lf = self.lgb
+            .agg([col(column).sum().alias("sum")])
+            .sort([&self.time_col], SortMultipleOptions::default());
+        QueryBuilder { lf }
+    }
+
+    /// Average a column per window
+    pub fn avg(self, column: &str) -> QueryBuilder {
+        let lf = self.lgb
+            .agg([col(column).mean().alias("avg")])
+            .sort([&self.time_col], SortMultipleOptions::default());
+        QueryBuilder { lf }
+    }
+
+    /// Min value per window
+    pub fn min(self, column: &str) -> QueryBuilder {
+        let lf = self.lgb
+            .agg([col(column).min().alias("min")])
+            .sort([&self.time_col], SortMultipleOptions::default());
+        QueryBuilder { lf }
+    }
+
+    /// Max value per window
+    pub fn max(self, column: &str) -> QueryBuilder {
+        let lf = self.lgb
+            .agg([col(column).max().alias("max")])
+            .sort([&self.time_col], SortMultipleOptions::default());
         QueryBuilder { lf }
     }
 }
@@ -195,6 +525,69 @@ fn parse_status_filter(filter: &str) -> Option<Expr> {
     None
 }
 
+/// Run an arbitrary SQL query against a parsed log frame, registered as
+/// `table_name`. Backed by Polars' own `SQLContext`, so this supports joins,
+/// window functions, and CASE expressions the `QueryBuilder` primitives don't
+/// expose.
+pub fn run_sql(lf: LazyFrame, table_name: &str, sql: &str) -> Result<DataFrame, QueryError> {
+    let mut ctx = SQLContext::new();
+    ctx.register(table_name, lf);
+    let result_lf = ctx.execute(sql)
+        .map_err(|e| QueryError::InvalidQuery(format!("{}: {}", sql, e)))?;
+    result_lf.collect().map_err(QueryError::from)
+}
+
+/// Serialization format for a query result, selectable per request (e.g. by
+/// the MCP layer's `output_format` param). Parquet and IPC, unlike JSON,
+/// preserve the DataFrame's dtypes exactly, so a round-tripped frame can
+/// re-enter a [`QueryBuilder`] (via [`dataframe_from_parquet`] /
+/// [`dataframe_from_ipc`]) without every column collapsing to strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Parquet,
+    Ipc,
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Some(OutputFormat::Json),
+            "parquet" => Some(OutputFormat::Parquet),
+            "ipc" | "arrow" | "feather" => Some(OutputFormat::Ipc),
+            _ => None,
+        }
+    }
+}
+
+/// Write a DataFrame as Parquet: compressed, with embedded per-column
+/// min/max statistics so a later scan over the archived file can push
+/// predicates down instead of decompressing every row group.
+pub fn dataframe_to_parquet<W: std::io::Write>(df: &DataFrame, writer: W) -> Result<(), QueryError> {
+    ParquetWriter::new(writer)
+        .finish(&mut df.clone())
+        .map_err(QueryError::from)?;
+    Ok(())
+}
+
+/// Write a DataFrame as Arrow IPC (a.k.a. Feather) for a zero-copy reload.
+pub fn dataframe_to_ipc<W: std::io::Write>(df: &DataFrame, writer: W) -> Result<(), QueryError> {
+    IpcWriter::new(writer)
+        .finish(&mut df.clone())
+        .map_err(QueryError::from)?;
+    Ok(())
+}
+
+/// Read a DataFrame back from Parquet bytes written by [`dataframe_to_parquet`].
+pub fn dataframe_from_parquet<R: std::io::Read + std::io::Seek>(reader: R) -> Result<DataFrame, QueryError> {
+    ParquetReader::new(reader).finish().map_err(QueryError::from)
+}
+
+/// Read a DataFrame back from Arrow IPC bytes written by [`dataframe_to_ipc`].
+pub fn dataframe_from_ipc<R: std::io::Read + std::io::Seek>(reader: R) -> Result<DataFrame, QueryError> {
+    IpcReader::new(reader).finish().map_err(QueryError::from)
+}
+
 /// Convert DataFrame to JSON string
 pub fn dataframe_to_json(df: &DataFrame) -> Result<String, QueryError> {
     let mut buf = Vec::new();
@@ -205,6 +598,79 @@ pub fn dataframe_to_json(df: &DataFrame) -> Result<String, QueryError> {
     String::from_utf8(buf).map_err(|e| QueryError::InvalidQuery(e.to_string()))
 }
 
+/// A time bucket whose count exceeded its rolling baseline by `k` standard
+/// deviations, surfaced by [`detect_spikes`].
+#[derive(Debug, Clone)]
+pub struct SpikeBucket {
+    pub bucket: String,
+    pub count: i64,
+    pub baseline_mean: f64,
+    pub baseline_stddev: f64,
+    pub z_score: f64,
+}
+
+/// Extract a sorted `(bucket, count)` series from a bucketed DataFrame
+/// (e.g. the output of a `group_by(time_col).count()`), so downstream
+/// analysis doesn't need to care whether the count column came back as
+/// `u32` or `i64`.
+pub fn bucket_series(df: &DataFrame, time_col: &str, count_col: &str) -> Result<Vec<(String, i64)>, QueryError> {
+    let time_col = df.column(time_col)?.cast(&DataType::String)?;
+    let time_values = time_col.str()?;
+    let count_col = df.column(count_col)?.cast(&DataType::Int64)?;
+    let count_values = count_col.i64()?;
+
+    let mut series: Vec<(String, i64)> = time_values
+        .into_iter()
+        .zip(count_values.into_iter())
+        .map(|(t, c)| (t.unwrap_or("").to_string(), c.unwrap_or(0)))
+        .collect();
+    series.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(series)
+}
+
+/// Walk a sorted `(bucket, count)` series in one linear pass and flag any
+/// bucket whose count exceeds `mean + k * stddev` of the preceding `window`
+/// buckets (a rolling baseline, recomputed fresh at each step). Buckets
+/// without enough preceding history to form a baseline (fewer than 2 prior
+/// buckets) are never flagged.
+pub fn detect_spikes(buckets: &[(String, i64)], window: usize, k: f64) -> Vec<SpikeBucket> {
+    let mut spikes = Vec::new();
+
+    for i in 0..buckets.len() {
+        let start = i.saturating_sub(window);
+        let history = &buckets[start..i];
+        if history.len() < 2 {
+            continue;
+        }
+
+        let n = history.len() as f64;
+        let mean = history.iter().map(|(_, c)| *c as f64).sum::<f64>() / n;
+        let variance = history.iter()
+            .map(|(_, c)| {
+                let d = *c as f64 - mean;
+                d * d
+            })
+            .sum::<f64>() / n;
+        let stddev = variance.sqrt();
+
+        let (bucket, count) = &buckets[i];
+        let count_f = *count as f64;
+
+        if count_f > mean + k * stddev {
+            let z_score = if stddev > 0.0 { (count_f - mean) / stddev } else { 0.0 };
+            spikes.push(SpikeBucket {
+                bucket: bucket.clone(),
+                count: *count,
+                baseline_mean: mean,
+                baseline_stddev: stddev,
+                z_score,
+            });
+        }
+    }
+
+    spikes
+}
+
 /// Get schema information from a DataFrame
 pub fn get_schema_info(df: &DataFrame) -> Vec<(String, String)> {
     df.schema()
@@ -13,11 +13,13 @@
 use memchr::memchr;
 use memmap2::Mmap;
 use rayon::prelude::*;
-use regex::bytes::Regex;
+use regex::bytes::{Regex, RegexSet};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
 use std::path::Path;
 
+use super::format_spec;
 use super::ParseError;
 
 /// Field positions within a log line (byte offsets)
@@ -38,35 +40,72 @@ struct FieldOffsets {
     user_agent_end: usize,
 }
 
-/// Find field boundaries using SIMD-accelerated byte search
-/// Returns None if line is malformed
+/// Why [`find_fields_diag`] rejected a line, independent of the byte
+/// offset reached — this is what [`scan_with_diagnostics`] tallies, so
+/// "200 lines missing the request quote" reads as one bucket regardless
+/// of where in the file each one occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseFaultKind {
+    /// Line is shorter than the minimum plausible combined-log line.
+    ShortLine,
+    /// No space found to end the leading IP/host field.
+    MissingSpaceAfterIp,
+    /// No `[` found to start the timestamp field.
+    MissingOpenBracket,
+    /// `[` found but no matching `]`.
+    UnterminatedBracket,
+    /// No opening or closing `"` found for the `"METHOD PATH PROTO"` field.
+    MissingRequestQuote,
+    /// Line ends before the 3-digit status code could be read in full.
+    TruncatedStatus,
+}
+
+/// A [`ParseFaultKind`] plus the byte offset into the line where parsing
+/// gave up, so a rejected-line sample can point at exactly where things
+/// went wrong.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseFault {
+    pub kind: ParseFaultKind,
+    pub offset: usize,
+}
+
+/// Find field boundaries using SIMD-accelerated byte search, naming the
+/// stage that failed (and the byte offset reached) instead of a bare
+/// `None`. [`find_fields`] is the hot-path wrapper most callers use; this
+/// is for [`scan_with_diagnostics`], which needs to explain *why* lines
+/// were rejected.
 #[inline]
-fn find_fields(line: &[u8]) -> Option<FieldOffsets> {
+fn find_fields_diag(line: &[u8]) -> Result<FieldOffsets, ParseFault> {
     let len = line.len();
     if len < 20 {
-        return None; // Too short to be valid
+        return Err(ParseFault { kind: ParseFaultKind::ShortLine, offset: len });
     }
 
     // IP ends at first space
-    let ip_end = memchr(b' ', line)?;
+    let ip_end = memchr(b' ', line)
+        .ok_or(ParseFault { kind: ParseFaultKind::MissingSpaceAfterIp, offset: 0 })?;
 
     // Timestamp is between [ and ]
-    let bracket_open = memchr(b'[', line)?;
+    let bracket_open = memchr(b'[', line)
+        .ok_or(ParseFault { kind: ParseFaultKind::MissingOpenBracket, offset: ip_end })?;
     let timestamp_start = bracket_open + 1;
-    let bracket_close = memchr(b']', &line[bracket_open..])?;
+    let bracket_close = memchr(b']', &line[bracket_open..])
+        .ok_or(ParseFault { kind: ParseFaultKind::UnterminatedBracket, offset: bracket_open })?;
     let timestamp_end = bracket_open + bracket_close;
 
     // Request is between first pair of quotes after timestamp
-    let quote1 = memchr(b'"', &line[timestamp_end..])?;
+    let quote1 = memchr(b'"', &line[timestamp_end..])
+        .ok_or(ParseFault { kind: ParseFaultKind::MissingRequestQuote, offset: timestamp_end })?;
     let request_start = timestamp_end + quote1 + 1;
-    let quote2 = memchr(b'"', &line[request_start..])?;
+    let quote2 = memchr(b'"', &line[request_start..])
+        .ok_or(ParseFault { kind: ParseFaultKind::MissingRequestQuote, offset: request_start })?;
     let request_end = request_start + quote2;
 
     // Status starts after the closing quote + space
     let status_start = request_end + 2; // skip '" '
 
     if status_start + 3 > len {
-        return None;
+        return Err(ParseFault { kind: ParseFaultKind::TruncatedStatus, offset: status_start });
     }
 
     // Size starts after status + space (status is 3 digits)
@@ -118,7 +157,7 @@ fn find_fields(line: &[u8]) -> Option<FieldOffsets> {
         (0, 0)
     };
 
-    Some(FieldOffsets {
+    Ok(FieldOffsets {
         ip_end,
         timestamp_start,
         timestamp_end,
@@ -134,6 +173,16 @@ fn find_fields(line: &[u8]) -> Option<FieldOffsets> {
     })
 }
 
+/// Find field boundaries using SIMD-accelerated byte search.
+/// Returns `None` if the line is malformed; see [`find_fields_diag`] for
+/// why. This is the hot-path entry point — every filter/aggregate
+/// function keeps using this and silently skipping `None` lines, so
+/// diagnostics mode is strictly additive.
+#[inline]
+fn find_fields(line: &[u8]) -> Option<FieldOffsets> {
+    find_fields_diag(line).ok()
+}
+
 /// Extract IP from line using pre-computed offsets
 #[inline]
 fn extract_ip<'a>(line: &'a [u8], offsets: &FieldOffsets) -> &'a [u8] {
@@ -241,8 +290,27 @@ const MONTHS: [(&[u8], u8); 12] = [
     (b"Sep", 9), (b"Oct", 10), (b"Nov", 11), (b"Dec", 12),
 ];
 
-/// Parse Apache timestamp to comparable i64 (YYYYMMDDHHmmss format)
-/// Input format: "16/Dec/2025:11:26:41 +0000"
+/// Days since the Unix epoch (1970-01-01) for a given civil (proleptic
+/// Gregorian) date, via Howard Hinnant's `days_from_civil` algorithm:
+/// treat Jan/Feb as months 13/14 of the prior year so the leap-day falls
+/// at the end of the computed year, then count days-of-era from a 400-year
+/// era anchored so `era * 146097 + doe - 719468` lands on the epoch.
+#[inline]
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400; // [0, 399]
+    let mp = (month + 9) % 12; // [0, 11]: Mar=0 .. Feb=11
+    let doy = (153 * mp + 2) / 5 + day - 1; // [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+    era * 146097 + doe - 719468
+}
+
+/// Parse an Apache timestamp to a true UTC epoch-second count (not just a
+/// sortable packed integer), so logs from different time zones compare
+/// correctly. Input format: "16/Dec/2025:11:26:41 +0000" — the trailing
+/// `±HHMM` offset (bytes 21-25) is read and subtracted out, since the
+/// calendar fields are local to that offset, not UTC.
 #[inline]
 fn parse_timestamp_to_i64(ts: &[u8]) -> Option<i64> {
     if ts.len() < 20 {
@@ -270,15 +338,20 @@ fn parse_timestamp_to_i64(ts: &[u8]) -> Option<i64> {
     // Second: bytes 18-19
     let second = parse_2digit(&ts[18..20])?;
 
-    // Combine into sortable i64: YYYYMMDDHHmmss
-    Some(
-        (year as i64) * 10000000000 +
-        (month as i64) * 100000000 +
-        (day as i64) * 1000000 +
-        (hour as i64) * 10000 +
-        (minute as i64) * 100 +
-        (second as i64)
-    )
+    let days = days_from_civil(year as i64, month as i64, day as i64);
+    let local_seconds = days * 86400 + (hour as i64) * 3600 + (minute as i64) * 60 + (second as i64);
+
+    // Trailing zone offset " +0000"/" -0500" at bytes 20-25, if present.
+    let offset_seconds = if ts.len() >= 26 && (ts[21] == b'+' || ts[21] == b'-') {
+        let offset_hh = parse_2digit(&ts[22..24])?;
+        let offset_mm = parse_2digit(&ts[24..26])?;
+        let magnitude = (offset_hh as i64) * 3600 + (offset_mm as i64) * 60;
+        if ts[21] == b'-' { -magnitude } else { magnitude }
+    } else {
+        0
+    };
+
+    Some(local_seconds - offset_seconds)
 }
 
 #[inline]
@@ -320,8 +393,16 @@ pub struct TimeFilter {
 
 impl TimeFilter {
     pub fn new(start: Option<&str>, end: Option<&str>) -> Option<Self> {
-        let start_val = start.and_then(|s| Self::parse_time_input(s));
-        let end_val = end.and_then(|s| Self::parse_time_input(s));
+        Self::new_with_zone(start, end, 0)
+    }
+
+    /// Like [`new`], but an offset-less input (a bare ISO date/time with no
+    /// trailing `Z`/`±HH:MM`) is assumed to be `assume_offset_minutes` east
+    /// of UTC instead of UTC itself. Apache-format inputs always carry
+    /// their own `±HHMM` offset and ignore this parameter.
+    pub fn new_with_zone(start: Option<&str>, end: Option<&str>, assume_offset_minutes: i32) -> Option<Self> {
+        let start_val = start.and_then(|s| Self::parse_time_input(s, assume_offset_minutes));
+        let end_val = end.and_then(|s| Self::parse_time_input(s, assume_offset_minutes));
 
         if start_val.is_none() && end_val.is_none() {
             return None;
@@ -333,38 +414,49 @@ impl TimeFilter {
         })
     }
 
-    /// Parse various time input formats to i64
-    /// Supports: "2025-12-16", "2025-12-16T11:26:41", "16/Dec/2025:11:26:41"
-    fn parse_time_input(s: &str) -> Option<i64> {
+    /// Parse various time input formats to a UTC epoch-second count, on
+    /// the same axis as [`parse_timestamp_to_i64`], so ISO inputs and
+    /// Apache log lines compare correctly across time zones.
+    /// Supports: "2025-12-16", "2025-12-16T11:26:41", "2025-12-16T11:26:41+05:30",
+    /// "2025-12-16T11:26:41Z", "16/Dec/2025:11:26:41 +0000"
+    fn parse_time_input(s: &str, assume_offset_minutes: i32) -> Option<i64> {
         let s = s.trim();
 
-        // ISO format: 2025-12-16 or 2025-12-16T11:26:41
+        // ISO format: 2025-12-16 or 2025-12-16T11:26:41[(Z|±HH:MM)]
         if s.len() >= 10 && s.as_bytes()[4] == b'-' {
-            let year = s[0..4].parse::<i32>().ok()?;
-            let month = s[5..7].parse::<i32>().ok()?;
-            let day = s[8..10].parse::<i32>().ok()?;
+            let year = s[0..4].parse::<i64>().ok()?;
+            let month = s[5..7].parse::<i64>().ok()?;
+            let day = s[8..10].parse::<i64>().ok()?;
 
-            let (hour, minute, second) = if s.len() >= 19 && s.as_bytes()[10] == b'T' {
+            let (hour, minute, second, rest) = if s.len() >= 19 && s.as_bytes()[10] == b'T' {
                 (
-                    s[11..13].parse::<i32>().ok()?,
-                    s[14..16].parse::<i32>().ok()?,
-                    s[17..19].parse::<i32>().ok()?,
+                    s[11..13].parse::<i64>().ok()?,
+                    s[14..16].parse::<i64>().ok()?,
+                    s[17..19].parse::<i64>().ok()?,
+                    &s[19..],
                 )
             } else {
-                (0, 0, 0)
+                (0, 0, 0, &s[s.len()..])
+            };
+
+            let days = days_from_civil(year, month, day);
+            let local_seconds = days * 86400 + hour * 3600 + minute * 60 + second;
+
+            let offset_seconds = match rest.as_bytes().first() {
+                Some(b'Z') => 0,
+                Some(b'+') | Some(b'-') if rest.len() >= 6 => {
+                    let sign = if rest.as_bytes()[0] == b'-' { -1 } else { 1 };
+                    let offset_hh: i64 = rest[1..3].parse().ok()?;
+                    let offset_mm: i64 = rest[4..6].parse().ok()?;
+                    sign * (offset_hh * 3600 + offset_mm * 60)
+                }
+                _ => (assume_offset_minutes as i64) * 60,
             };
 
-            return Some(
-                (year as i64) * 10000000000 +
-                (month as i64) * 100000000 +
-                (day as i64) * 1000000 +
-                (hour as i64) * 10000 +
-                (minute as i64) * 100 +
-                (second as i64)
-            );
+            return Some(local_seconds - offset_seconds);
         }
 
-        // Apache format: 16/Dec/2025:11:26:41
+        // Apache format: 16/Dec/2025:11:26:41 +0000
         if s.len() >= 20 && s.as_bytes()[2] == b'/' {
             return parse_timestamp_to_i64(s.as_bytes());
         }
@@ -460,6 +552,396 @@ impl GroupByColumn {
     }
 }
 
+/// Extract the bytes of `column` from `line` using pre-computed `offsets`.
+/// Shared by [`group_by_count`]'s key extraction and [`Predicate`] field
+/// leaves so both agree on what e.g. "path" means.
+#[inline]
+fn field_bytes<'a>(line: &'a [u8], offsets: &FieldOffsets, column: GroupByColumn) -> &'a [u8] {
+    match column {
+        GroupByColumn::Ip => extract_ip(line, offsets),
+        GroupByColumn::Path => extract_path(line, offsets),
+        GroupByColumn::Method => extract_method(line, offsets),
+        GroupByColumn::Status => {
+            let start = offsets.status_start;
+            if start + 3 <= line.len() {
+                &line[start..start + 3]
+            } else {
+                b"???"
+            }
+        }
+        GroupByColumn::Referer => extract_referer(line, offsets),
+        GroupByColumn::UserAgent => extract_user_agent(line, offsets),
+    }
+}
+
+/// Compiles a set of include/exclude path globs (e.g. `/api/*`) into two
+/// `RegexSet`s built from a single alternation each, so a line's request
+/// path is tested with two `is_match` calls regardless of how many globs
+/// were given, and the compiled matcher is built once and shared across
+/// every parallel chunk rather than re-parsed per line. An empty include
+/// set means "match all paths"; a path matches only if it matches some
+/// include glob (or the include set is empty) AND no exclude glob.
+pub struct PathMatcher {
+    include: Option<RegexSet>,
+    exclude: Option<RegexSet>,
+}
+
+impl PathMatcher {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, ParseError> {
+        let compile = |globs: &[String]| -> Result<Option<RegexSet>, ParseError> {
+            if globs.is_empty() {
+                return Ok(None);
+            }
+            let patterns: Vec<String> = globs.iter().map(|g| glob_to_path_regex(g)).collect();
+            RegexSet::new(&patterns)
+                .map(Some)
+                .map_err(|e| ParseError::ParseFailed(format!("Invalid path glob: {}", e)))
+        };
+
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    #[inline]
+    pub fn matches(&self, path: &[u8]) -> bool {
+        let included = match &self.include {
+            Some(set) => set.is_match(path),
+            None => true,
+        };
+        if !included {
+            return false;
+        }
+        match &self.exclude {
+            Some(set) => !set.is_match(path),
+            None => true,
+        }
+    }
+}
+
+/// Translate one glob pattern (`*` matches any run of bytes, `?` matches
+/// any single byte) into an anchored regex, escaping every other
+/// regex-special character so literal path segments match literally.
+fn glob_to_path_regex(glob: &str) -> String {
+    let mut out = String::with_capacity(glob.len() + 2);
+    out.push('^');
+    for c in glob.chars() {
+        match c {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            '.' | '+' | '(' | ')' | '|' | '^' | '$' | '[' | ']' | '{' | '}' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out.push('$');
+    out
+}
+
+// ============================================================================
+// PREDICATE: composable boolean query language (AND/OR/NOT) over fields
+// ============================================================================
+
+/// A boolean query over a log line, built from leaves wrapping the existing
+/// filters plus field equality/regex, combined with `And`/`Or`/`Not`. See
+/// [`parse_predicate`] for the string syntax and [`predicate_matches`] for
+/// evaluation.
+#[derive(Debug, Clone)]
+pub enum Predicate {
+    /// Matches every line unconditionally; the identity for `And`, used
+    /// when no filter was supplied at all.
+    All,
+    Status(StatusFilter),
+    Time(TimeFilter),
+    Text(Vec<u8>),
+    FieldEquals(GroupByColumn, Vec<u8>),
+    FieldRegex(GroupByColumn, Regex),
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+impl Predicate {
+    /// Build a predicate equivalent to the old trio of separate optional
+    /// filters, ANDed together (or `All` if none are set). Lets callers
+    /// that still think in terms of discrete status/time/text filters
+    /// (e.g. the MCP tool params) build a `Predicate` without hand-rolling
+    /// the combinator tree themselves.
+    pub fn from_options(status: Option<StatusFilter>, time: Option<TimeFilter>, text: Option<&[u8]>) -> Self {
+        let mut combined: Option<Predicate> = None;
+        let mut and_in = |p: Predicate, combined: &mut Option<Predicate>| {
+            *combined = Some(match combined.take() {
+                Some(existing) => Predicate::And(Box::new(existing), Box::new(p)),
+                None => p,
+            });
+        };
+
+        if let Some(s) = status {
+            and_in(Predicate::Status(s), &mut combined);
+        }
+        if let Some(t) = time {
+            and_in(Predicate::Time(t), &mut combined);
+        }
+        if let Some(txt) = text {
+            and_in(Predicate::Text(txt.to_vec()), &mut combined);
+        }
+
+        combined.unwrap_or(Predicate::All)
+    }
+}
+
+/// Lazily-computed per-line context so a predicate branch that only needs
+/// e.g. the status field never pays for parsing fields it doesn't touch;
+/// `offsets` is computed once (on first access) and shared by every leaf
+/// that needs it.
+struct EvalContext<'a> {
+    line: &'a [u8],
+    offsets: Option<FieldOffsets>,
+    offsets_computed: bool,
+}
+
+impl<'a> EvalContext<'a> {
+    fn new(line: &'a [u8]) -> Self {
+        EvalContext { line, offsets: None, offsets_computed: false }
+    }
+
+    fn offsets(&mut self) -> Option<FieldOffsets> {
+        if !self.offsets_computed {
+            self.offsets = find_fields(self.line);
+            self.offsets_computed = true;
+        }
+        self.offsets
+    }
+}
+
+fn eval_predicate(pred: &Predicate, ctx: &mut EvalContext) -> bool {
+    match pred {
+        Predicate::All => true,
+        Predicate::Status(filter) => ctx.offsets()
+            .and_then(|offsets| extract_status(ctx.line, &offsets))
+            .is_some_and(|status| filter.matches(status)),
+        Predicate::Time(filter) => ctx.offsets()
+            .and_then(|offsets| parse_timestamp_to_i64(extract_timestamp(ctx.line, &offsets)))
+            .is_some_and(|ts| filter.matches(ts)),
+        Predicate::Text(needle) => memchr::memmem::find(ctx.line, needle).is_some(),
+        Predicate::FieldEquals(column, value) => ctx.offsets()
+            .is_some_and(|offsets| field_bytes(ctx.line, &offsets, *column) == value.as_slice()),
+        Predicate::FieldRegex(column, re) => ctx.offsets()
+            .is_some_and(|offsets| re.is_match(field_bytes(ctx.line, &offsets, *column))),
+        Predicate::And(a, b) => eval_predicate(a, ctx) && eval_predicate(b, ctx),
+        Predicate::Or(a, b) => eval_predicate(a, ctx) || eval_predicate(b, ctx),
+        Predicate::Not(a) => !eval_predicate(a, ctx),
+    }
+}
+
+/// Evaluate `pred` against a single log line.
+pub fn predicate_matches(pred: &Predicate, line: &[u8]) -> bool {
+    let mut ctx = EvalContext::new(line);
+    eval_predicate(pred, &mut ctx)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    Leaf(String),
+}
+
+/// Split `input` into tokens, treating `(`/`)` as standalone, `AND`/`OR`/
+/// `NOT` (case-insensitive) as keywords, and everything else (including a
+/// `"quoted string"` that may itself contain spaces) as a single leaf.
+fn tokenize(input: &str) -> Vec<Token> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '(' {
+            tokens.push(Token::LParen);
+            i += 1;
+            continue;
+        }
+        if c == ')' {
+            tokens.push(Token::RParen);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '(' && chars[i] != ')' {
+            if chars[i] == '"' {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    i += 1;
+                }
+                if i < chars.len() {
+                    i += 1; // consume closing quote
+                }
+            } else {
+                i += 1;
+            }
+        }
+
+        let word: String = chars[start..i].iter().collect();
+        match word.to_uppercase().as_str() {
+            "AND" => tokens.push(Token::And),
+            "OR" => tokens.push(Token::Or),
+            "NOT" => tokens.push(Token::Not),
+            _ => tokens.push(Token::Leaf(word)),
+        }
+    }
+
+    tokens
+}
+
+fn unquote(s: &str) -> String {
+    s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(s).to_string()
+}
+
+/// Parse a single leaf term: `status:>=500`, `time:>=2025-01-01`,
+/// `path:"/admin"` (equality), `ua~"bot"` (regex), or a bare/quoted string
+/// with no field prefix (plain substring [`Predicate::Text`]).
+fn parse_leaf(text: &str) -> Result<Predicate, ParseError> {
+    if let Some(rest) = text.strip_prefix("status:") {
+        return StatusFilter::parse(rest)
+            .map(Predicate::Status)
+            .ok_or_else(|| ParseError::ParseFailed(format!("Invalid status filter: {}", rest)));
+    }
+
+    if let Some(rest) = text.strip_prefix("time:") {
+        let rest = rest.trim();
+        if let Some(bound) = rest.strip_prefix(">=").or_else(|| rest.strip_prefix('>')) {
+            return TimeFilter::new(Some(bound.trim()), None)
+                .map(Predicate::Time)
+                .ok_or_else(|| ParseError::ParseFailed(format!("Invalid time filter: {}", rest)));
+        }
+        if let Some(bound) = rest.strip_prefix("<=").or_else(|| rest.strip_prefix('<')) {
+            return TimeFilter::new(None, Some(bound.trim()))
+                .map(Predicate::Time)
+                .ok_or_else(|| ParseError::ParseFailed(format!("Invalid time filter: {}", rest)));
+        }
+        return Err(ParseError::ParseFailed(format!("time: filter requires a >=/<= bound: {}", rest)));
+    }
+
+    if let Some(tilde_pos) = text.find('~') {
+        let field = &text[..tilde_pos];
+        let pattern = unquote(&text[tilde_pos + 1..]);
+        let column = GroupByColumn::parse(field)
+            .ok_or_else(|| ParseError::ParseFailed(format!("Unknown field: {}", field)))?;
+        let re = Regex::new(&pattern)
+            .map_err(|e| ParseError::ParseFailed(format!("Invalid regex '{}': {}", pattern, e)))?;
+        return Ok(Predicate::FieldRegex(column, re));
+    }
+
+    if let Some(colon_pos) = text.find(':') {
+        let field = &text[..colon_pos];
+        if let Some(column) = GroupByColumn::parse(field) {
+            let value = unquote(&text[colon_pos + 1..]);
+            return Ok(Predicate::FieldEquals(column, value.into_bytes()));
+        }
+        // Unrecognized "field:value" shape falls through to plain text so a
+        // colon in a quoted search term (e.g. a URL) doesn't hard-error.
+    }
+
+    Ok(Predicate::Text(unquote(text).into_bytes()))
+}
+
+struct PredicateParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl PredicateParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    // or := and (OR and)*
+    fn parse_or(&mut self) -> Result<Predicate, ParseError> {
+        let mut left = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Predicate::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // and := not (AND not)*
+    fn parse_and(&mut self) -> Result<Predicate, ParseError> {
+        let mut left = self.parse_not()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.pos += 1;
+            let right = self.parse_not()?;
+            left = Predicate::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // not := NOT not | atom
+    fn parse_not(&mut self) -> Result<Predicate, ParseError> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.pos += 1;
+            return Ok(Predicate::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    // atom := '(' or ')' | leaf
+    fn parse_atom(&mut self) -> Result<Predicate, ParseError> {
+        match self.advance() {
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    other => Err(ParseError::ParseFailed(format!("Expected ')', found {:?}", other))),
+                }
+            }
+            Some(Token::Leaf(text)) => parse_leaf(&text),
+            other => Err(ParseError::ParseFailed(format!("Unexpected token: {:?}", other))),
+        }
+    }
+}
+
+/// Parse a query string like `status:>=500 AND (path~"^/api" OR NOT ua:"bot")`
+/// into a [`Predicate`] tree via recursive descent. Operator precedence
+/// (loosest to tightest): `OR`, `AND`, `NOT`; parentheses override it.
+pub fn parse_predicate(input: &str) -> Result<Predicate, ParseError> {
+    let tokens = tokenize(input);
+    if tokens.is_empty() {
+        return Err(ParseError::ParseFailed("Empty predicate".to_string()));
+    }
+
+    let mut parser = PredicateParser { tokens, pos: 0 };
+    let result = parser.parse_or()?;
+
+    if parser.pos != parser.tokens.len() {
+        return Err(ParseError::ParseFailed(format!(
+            "Unexpected trailing token at position {}: {:?}",
+            parser.pos, parser.tokens.get(parser.pos)
+        )));
+    }
+
+    Ok(result)
+}
+
 // ============================================================================
 // GREP-LIKE FAST COUNTING (no parsing, maximum speed)
 // ============================================================================
@@ -553,13 +1035,67 @@ pub fn count_matches_multi(paths: &[&Path], pattern: &str) -> Result<usize, Pars
     Ok(count)
 }
 
+// ============================================================================
+// CONFIGURABLE FORMAT SCAN
+// ============================================================================
+
+/// Count lines in `path` that parse against `format_string` (an
+/// Apache-style `LogFormat` directive, e.g. nginx's default combined
+/// format translates to `%h %l %u %t "%r" %>s %b "%{Referer}i"
+/// "%{User-Agent}i"`). Unlike [`count_status`]/[`count_matches`], this
+/// doesn't assume the combined-log fixed offsets, so it also works for
+/// nginx logs, vhost-prefixed logs, and other custom `LogFormat`s —
+/// [`format_spec::find_fields_with_spec`] walks each line against the
+/// compiled [`format_spec::FieldSpec`] list instead.
+pub fn count_matching_format(path: &Path, format_string: &str) -> Result<usize, ParseError> {
+    let specs = format_spec::parse_format_string(format_string);
+    if specs.is_empty() {
+        return Err(ParseError::ParseFailed(format!(
+            "Could not parse any fields from format string: {}",
+            format_string
+        )));
+    }
+
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    let count: usize = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut local_count = 0;
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                if format_spec::find_fields_with_spec(line, &specs).is_some() {
+                    local_count += 1;
+                }
+
+                pos = line_end + 1;
+            }
+            local_count
+        })
+        .sum();
+
+    Ok(count)
+}
+
 // ============================================================================
 // GENERALIZED FAST OPERATIONS
 // ============================================================================
 
-/// Count lines matching a status filter - accurate version
+/// Count lines matching `predicate` - accurate version
 /// No false positives because we properly parse field boundaries
-pub fn count_status(path: &Path, filter: StatusFilter) -> Result<usize, ParseError> {
+pub fn count_status(path: &Path, predicate: &Predicate) -> Result<usize, ParseError> {
     let file = File::open(path)?;
     let mmap = unsafe { Mmap::map(&file)? };
     let data = &mmap[..];
@@ -581,12 +1117,8 @@ pub fn count_status(path: &Path, filter: StatusFilter) -> Result<usize, ParseErr
                     .unwrap_or(chunk.len());
                 let line = &chunk[pos..line_end];
 
-                if let Some(offsets) = find_fields(line) {
-                    if let Some(status) = extract_status(line, &offsets) {
-                        if filter.matches(status) {
-                            local_count += 1;
-                        }
-                    }
+                if predicate_matches(predicate, line) {
+                    local_count += 1;
                 }
 
                 pos = line_end + 1;
@@ -598,13 +1130,11 @@ pub fn count_status(path: &Path, filter: StatusFilter) -> Result<usize, ParseErr
     Ok(count)
 }
 
-/// Filter lines matching status and optionally text pattern
+/// Filter lines matching `predicate`.
 /// Returns (total_count, matching_lines)
 pub fn filter_lines(
     path: &Path,
-    status_filter: Option<StatusFilter>,
-    time_filter: Option<TimeFilter>,
-    text_pattern: Option<&[u8]>,
+    predicate: &Predicate,
     limit: usize,
 ) -> Result<(usize, Vec<String>), ParseError> {
     let file = File::open(path)?;
@@ -614,9 +1144,6 @@ pub fn filter_lines(
     let chunk_size = 4 * 1024 * 1024;
     let chunk_bounds = find_chunk_boundaries(data, chunk_size);
 
-    // Use memmem for text pattern if provided
-    let text_finder = text_pattern.map(memchr::memmem::Finder::new);
-
     // Parallel scan with early termination awareness
     let results: Vec<(usize, Vec<&[u8]>)> = chunk_bounds
         .par_windows(2)
@@ -632,53 +1159,7 @@ pub fn filter_lines(
                     .unwrap_or(chunk.len());
                 let line = &chunk[pos..line_end];
 
-                let mut matches = true;
-                let mut offsets_cached: Option<FieldOffsets> = None;
-
-                // Check status filter or time filter (both need field parsing)
-                if status_filter.is_some() || time_filter.is_some() {
-                    if let Some(offsets) = find_fields(line) {
-                        offsets_cached = Some(offsets);
-
-                        // Check status filter
-                        if let Some(ref filter) = status_filter {
-                            if let Some(status) = extract_status(line, &offsets) {
-                                if !filter.matches(status) {
-                                    matches = false;
-                                }
-                            } else {
-                                matches = false;
-                            }
-                        }
-
-                        // Check time filter
-                        if matches {
-                            if let Some(ref tfilter) = time_filter {
-                                let ts = extract_timestamp(line, &offsets);
-                                if let Some(ts_i64) = parse_timestamp_to_i64(ts) {
-                                    if !tfilter.matches(ts_i64) {
-                                        matches = false;
-                                    }
-                                } else {
-                                    matches = false;
-                                }
-                            }
-                        }
-                    } else {
-                        matches = false;
-                    }
-                }
-
-                // Check text pattern (only if previous filters matched)
-                if matches {
-                    if let Some(ref finder) = text_finder {
-                        if finder.find(line).is_none() {
-                            matches = false;
-                        }
-                    }
-                }
-
-                if matches {
+                if predicate_matches(predicate, line) {
                     local_count += 1;
                     local_lines.push(line);
                 }
@@ -702,12 +1183,14 @@ pub fn filter_lines(
     Ok((total_count, lines))
 }
 
-/// Group by any column with count aggregation
+/// Group by any column with count aggregation, restricted to lines matching
+/// `predicate` and, if given, scoped to request paths accepted by
+/// `path_matcher` (e.g. "count 5xx under /checkout but not /checkout/health").
 pub fn group_by_count(
     path: &Path,
     column: GroupByColumn,
-    status_filter: Option<StatusFilter>,
-    text_pattern: Option<&[u8]>,
+    predicate: &Predicate,
+    path_matcher: Option<&PathMatcher>,
 ) -> Result<Vec<(String, u64)>, ParseError> {
     let file = File::open(path)?;
     let mmap = unsafe { Mmap::map(&file)? };
@@ -716,8 +1199,6 @@ pub fn group_by_count(
     let chunk_size = 4 * 1024 * 1024;
     let chunk_bounds = find_chunk_boundaries(data, chunk_size);
 
-    let text_finder = text_pattern.map(memchr::memmem::Finder::new);
-
     // Each chunk builds a local HashMap
     let local_maps: Vec<HashMap<Vec<u8>, u64>> = chunk_bounds
         .par_windows(2)
@@ -732,49 +1213,16 @@ pub fn group_by_count(
                     .unwrap_or(chunk.len());
                 let line = &chunk[pos..line_end];
 
-                if let Some(offsets) = find_fields(line) {
-                    let mut matches = true;
-
-                    // Apply status filter
-                    if let Some(ref filter) = status_filter {
-                        if let Some(status) = extract_status(line, &offsets) {
-                            if !filter.matches(status) {
-                                matches = false;
-                            }
-                        } else {
-                            matches = false;
-                        }
-                    }
-
-                    // Apply text filter
-                    if matches {
-                        if let Some(ref finder) = text_finder {
-                            if finder.find(line).is_none() {
-                                matches = false;
-                            }
-                        }
-                    }
-
-                    if matches {
-                        // Extract the grouping key
-                        let key: &[u8] = match column {
-                            GroupByColumn::Ip => extract_ip(line, &offsets),
-                            GroupByColumn::Path => extract_path(line, &offsets),
-                            GroupByColumn::Method => extract_method(line, &offsets),
-                            GroupByColumn::Status => {
-                                // For status, we use the raw 3 bytes
-                                let start = offsets.status_start;
-                                if start + 3 <= line.len() {
-                                    &line[start..start + 3]
-                                } else {
-                                    b"???"
-                                }
-                            }
-                            GroupByColumn::Referer => extract_referer(line, &offsets),
-                            GroupByColumn::UserAgent => extract_user_agent(line, &offsets),
+                if predicate_matches(predicate, line) {
+                    if let Some(offsets) = find_fields(line) {
+                        let path_ok = match path_matcher {
+                            Some(m) => m.matches(extract_path(line, &offsets)),
+                            None => true,
                         };
-
-                        *counts.entry(key.to_vec()).or_insert(0) += 1;
+                        if path_ok {
+                            let key = field_bytes(line, &offsets, column);
+                            *counts.entry(key.to_vec()).or_insert(0) += 1;
+                        }
                     }
                 }
 
@@ -832,25 +1280,50 @@ fn find_chunk_boundaries(data: &[u8], chunk_size: usize) -> Vec<usize> {
 // ============================================================================
 
 /// Count across multiple files (glob pattern support)
-pub fn count_status_multi(paths: &[&Path], filter: StatusFilter) -> Result<usize, ParseError> {
+pub fn count_status_multi(paths: &[&Path], predicate: &Predicate) -> Result<usize, ParseError> {
     let counts: Result<Vec<usize>, ParseError> = paths
         .par_iter()
-        .map(|path| count_status(path, filter))
+        .map(|path| count_status(path, predicate))
         .collect();
 
     Ok(counts?.into_iter().sum())
 }
 
+/// Filter lines across multiple files, applying the same `predicate` as
+/// [`filter_lines`]. Files are scanned in order and scanning stops as soon
+/// as `limit` matching lines have been collected, so a glob over many
+/// rotated files doesn't keep scanning once there's enough to show.
+pub fn filter_lines_multi(
+    paths: &[&Path],
+    predicate: &Predicate,
+    limit: usize,
+) -> Result<(usize, Vec<String>), ParseError> {
+    let mut total_count = 0usize;
+    let mut lines: Vec<String> = Vec::new();
+
+    for path in paths {
+        if lines.len() >= limit {
+            break;
+        }
+        let remaining = limit - lines.len();
+        let (count, mut file_lines) = filter_lines(path, predicate, remaining)?;
+        total_count += count;
+        lines.append(&mut file_lines);
+    }
+
+    Ok((total_count, lines))
+}
+
 /// Group by across multiple files
 pub fn group_by_count_multi(
     paths: &[&Path],
     column: GroupByColumn,
-    status_filter: Option<StatusFilter>,
-    text_pattern: Option<&[u8]>,
+    predicate: &Predicate,
+    path_matcher: Option<&PathMatcher>,
 ) -> Result<Vec<(String, u64)>, ParseError> {
     let local_results: Result<Vec<Vec<(String, u64)>>, ParseError> = paths
         .par_iter()
-        .map(|path| group_by_count(path, column, status_filter, text_pattern))
+        .map(|path| group_by_count(path, column, predicate, path_matcher))
         .collect();
 
     // Merge all results
@@ -867,16 +1340,149 @@ pub fn group_by_count_multi(
     Ok(result)
 }
 
-// ============================================================================
-// REGEX SEARCH (uses regex crate with SIMD optimizations)
-// ============================================================================
+/// Separates joined named-capture values in a [`group_by_regex`] composite
+/// key; a capture's text containing this byte would otherwise be
+/// indistinguishable from a different split between captures.
+const REGEX_GROUP_KEY_SEP: u8 = 0;
+
+/// Group lines by the concatenation of a user regex's named capture
+/// groups, for log layouts [`GroupByColumn`] doesn't model natively
+/// (JSON-ish logs, custom delimiters, URL query parameters, ...). Reuses
+/// the same per-chunk `HashMap<Vec<u8>, u64>` scan and merge as
+/// [`group_by_count`], just building the key from `regex.captures(line)`
+/// instead of a fixed `extract_*` field, and composes with the same
+/// `predicate`/`path_matcher` filters.
+pub fn group_by_regex(
+    path: &Path,
+    pattern: &str,
+    predicate: &Predicate,
+    path_matcher: Option<&PathMatcher>,
+) -> Result<Vec<(String, u64)>, ParseError> {
+    let regex = Regex::new(pattern)
+        .map_err(|e| ParseError::ParseFailed(format!("Invalid regex: {}", e)))?;
+
+    let group_names: Vec<&str> = regex.capture_names().flatten().collect();
+    if group_names.is_empty() {
+        return Err(ParseError::ParseFailed(
+            "group_by_regex requires at least one named capture group, e.g. (?P<key>\\w+)".to_string(),
+        ));
+    }
+
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    let local_maps: Vec<HashMap<Vec<u8>, u64>> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut counts: HashMap<Vec<u8>, u64> = HashMap::new();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                if predicate_matches(predicate, line) {
+                    let path_ok = match path_matcher {
+                        Some(m) => match find_fields(line) {
+                            Some(offsets) => m.matches(extract_path(line, &offsets)),
+                            None => false,
+                        },
+                        None => true,
+                    };
+
+                    if path_ok {
+                        if let Some(caps) = regex.captures(line) {
+                            let mut key: Vec<u8> = Vec::new();
+                            for (i, name) in group_names.iter().enumerate() {
+                                if i > 0 {
+                                    key.push(REGEX_GROUP_KEY_SEP);
+                                }
+                                if let Some(m) = caps.name(name) {
+                                    key.extend_from_slice(m.as_bytes());
+                                }
+                            }
+                            *counts.entry(key).or_insert(0) += 1;
+                        }
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            counts
+        })
+        .collect();
+
+    let mut global_counts: HashMap<Vec<u8>, u64> = HashMap::new();
+    for local in local_maps {
+        for (key, count) in local {
+            *global_counts.entry(key).or_insert(0) += count;
+        }
+    }
+
+    // Re-join the composite key with a human-readable separator now that
+    // the NUL-byte split is no longer needed for correctness.
+    let mut result: Vec<(String, u64)> = global_counts
+        .into_iter()
+        .map(|(key, count)| {
+            let key_str = key
+                .split(|&b| b == REGEX_GROUP_KEY_SEP)
+                .map(|part| String::from_utf8_lossy(part).into_owned())
+                .collect::<Vec<String>>()
+                .join("|");
+            (key_str, count)
+        })
+        .collect();
+
+    result.sort_by(|a, b| b.1.cmp(&a.1));
+
+    Ok(result)
+}
+
+/// Like [`group_by_regex`], but across multiple files.
+pub fn group_by_regex_multi(
+    paths: &[&Path],
+    pattern: &str,
+    predicate: &Predicate,
+    path_matcher: Option<&PathMatcher>,
+) -> Result<Vec<(String, u64)>, ParseError> {
+    let local_results: Result<Vec<Vec<(String, u64)>>, ParseError> = paths
+        .par_iter()
+        .map(|path| group_by_regex(path, pattern, predicate, path_matcher))
+        .collect();
+
+    let mut global_counts: HashMap<String, u64> = HashMap::new();
+    for results in local_results? {
+        for (key, count) in results {
+            *global_counts.entry(key).or_insert(0) += count;
+        }
+    }
+
+    let mut result: Vec<(String, u64)> = global_counts.into_iter().collect();
+    result.sort_by(|a, b| b.1.cmp(&a.1));
+
+    Ok(result)
+}
+
+// ============================================================================
+// REGEX SEARCH (uses regex crate with SIMD optimizations)
+// ============================================================================
 
-/// Search for regex pattern in log lines - SIMD accelerated via regex crate
+/// Search for regex pattern in log lines - SIMD accelerated via regex
+/// crate, optionally scoped to request paths accepted by `path_matcher`.
 pub fn regex_search(
     path: &Path,
     pattern: &str,
     status_filter: Option<StatusFilter>,
     limit: usize,
+    path_matcher: Option<&PathMatcher>,
 ) -> Result<(usize, Vec<String>), ParseError> {
     let file = File::open(path)?;
     let mmap = unsafe { Mmap::map(&file)? };
@@ -921,6 +1527,20 @@ pub fn regex_search(
                     }
                 }
 
+                // Apply path include/exclude glob filter if provided
+                if matches {
+                    if let Some(m) = path_matcher {
+                        match find_fields(line) {
+                            Some(offsets) => {
+                                if !m.matches(extract_path(line, &offsets)) {
+                                    matches = false;
+                                }
+                            }
+                            None => matches = false,
+                        }
+                    }
+                }
+
                 if matches {
                     local_count += 1;
                     local_lines.push(line);
@@ -944,6 +1564,109 @@ pub fn regex_search(
     Ok((total_count, lines))
 }
 
+/// A matched line tagged with the index (stringified) of every pattern in
+/// the `RegexSet` that fired on it.
+#[derive(Debug, Clone)]
+pub struct TaggedMatch {
+    pub line: String,
+    pub labels: Vec<String>,
+}
+
+/// Search for many regex patterns in a single pass per line using a
+/// `regex::bytes::RegexSet`, rather than running one `regex_search` per
+/// pattern. Returns the total number of lines matching at least one
+/// pattern plus a sample of matches (bounded by `limit`), each tagged with
+/// the index of every pattern that fired.
+pub fn regex_search_multi(
+    path: &Path,
+    patterns: &[String],
+    status_filter: Option<StatusFilter>,
+    limit: usize,
+    path_matcher: Option<&PathMatcher>,
+) -> Result<(usize, Vec<TaggedMatch>), ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let set = RegexSet::new(patterns)
+        .map_err(|e| ParseError::ParseFailed(format!("Invalid regex set: {}", e)))?;
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    let results: Vec<(usize, Vec<TaggedMatch>)> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut local_count = 0;
+            let mut local_samples: Vec<TaggedMatch> = Vec::new();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                let hits = set.matches(line);
+                let mut any_match = hits.matched_any();
+
+                if any_match {
+                    if let Some(ref filter) = status_filter {
+                        if let Some(offsets) = find_fields(line) {
+                            if let Some(status) = extract_status(line, &offsets) {
+                                if !filter.matches(status) {
+                                    any_match = false;
+                                }
+                            } else {
+                                any_match = false;
+                            }
+                        } else {
+                            any_match = false;
+                        }
+                    }
+                }
+
+                if any_match {
+                    if let Some(m) = path_matcher {
+                        match find_fields(line) {
+                            Some(offsets) => {
+                                if !m.matches(extract_path(line, &offsets)) {
+                                    any_match = false;
+                                }
+                            }
+                            None => any_match = false,
+                        }
+                    }
+                }
+
+                if any_match {
+                    local_count += 1;
+                    if let Ok(text) = std::str::from_utf8(line) {
+                        local_samples.push(TaggedMatch {
+                            line: text.to_string(),
+                            labels: hits.iter().map(|i| i.to_string()).collect(),
+                        });
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            (local_count, local_samples)
+        })
+        .collect();
+
+    let total_count: usize = results.iter().map(|(c, _)| c).sum();
+    let samples: Vec<TaggedMatch> = results
+        .into_iter()
+        .flat_map(|(_, samples)| samples)
+        .take(limit)
+        .collect();
+
+    Ok((total_count, samples))
+}
+
 // ============================================================================
 // NUMERIC AGGREGATIONS (sum, avg, min, max on size field)
 // ============================================================================
@@ -958,7 +1681,7 @@ pub enum AggOp {
 }
 
 /// Result of numeric aggregation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggResult {
     pub sum: i64,
     pub count: u64,
@@ -992,12 +1715,14 @@ impl AggResult {
     }
 }
 
-/// Aggregate size field with optional grouping
+/// Aggregate size field with optional grouping, optionally scoped to
+/// request paths accepted by `path_matcher`.
 pub fn aggregate_size(
     path: &Path,
     group_by: Option<GroupByColumn>,
     status_filter: Option<StatusFilter>,
     text_pattern: Option<&[u8]>,
+    path_matcher: Option<&PathMatcher>,
 ) -> Result<HashMap<String, AggResult>, ParseError> {
     let file = File::open(path)?;
     let mmap = unsafe { Mmap::map(&file)? };
@@ -1043,6 +1768,15 @@ pub fn aggregate_size(
                         }
                     }
 
+                    // Apply path include/exclude glob filter
+                    if matches {
+                        if let Some(m) = path_matcher {
+                            if !m.matches(extract_path(line, &offsets)) {
+                                matches = false;
+                            }
+                        }
+                    }
+
                     if matches {
                         if let Some(size) = extract_size(line, &offsets) {
                             let key = match group_by {
@@ -1099,10 +1833,11 @@ pub fn aggregate_size_multi(
     group_by: Option<GroupByColumn>,
     status_filter: Option<StatusFilter>,
     text_pattern: Option<&[u8]>,
+    path_matcher: Option<&PathMatcher>,
 ) -> Result<HashMap<String, AggResult>, ParseError> {
     let local_results: Result<Vec<HashMap<String, AggResult>>, ParseError> = paths
         .par_iter()
-        .map(|path| aggregate_size(path, group_by, status_filter, text_pattern))
+        .map(|path| aggregate_size(path, group_by, status_filter, text_pattern, path_matcher))
         .collect();
 
     let mut global_aggs: HashMap<String, AggResult> = HashMap::new();
@@ -1118,71 +1853,1229 @@ pub fn aggregate_size_multi(
     Ok(global_aggs)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_find_fields() {
-        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /index.html HTTP/1.1\" 200 2326";
-        let offsets = find_fields(line).expect("Should parse");
+/// Which numeric field [`histogram_aggregate`] buckets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistogramField {
+    Size,
+    Status,
+}
 
-        assert_eq!(&line[..offsets.ip_end], b"192.168.1.1");
-        assert_eq!(
-            &line[offsets.request_start..offsets.request_end],
-            b"GET /index.html HTTP/1.1"
-        );
+impl HistogramField {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "size" | "bytes" | "body_bytes_sent" => Some(HistogramField::Size),
+            "status" | "status_code" | "http_status" => Some(HistogramField::Status),
+            _ => None,
+        }
+    }
+}
 
-        let status = extract_status(line, &offsets);
-        assert_eq!(status, Some(200));
+/// The bucket lower bound a `value` falls into for a `width`/`offset`
+/// fixed-width histogram: `((value - offset).div_euclid(width)) * width +
+/// offset`. Uses `div_euclid` (not plain `/`) so values below `offset`
+/// still bucket correctly instead of rounding toward zero.
+#[inline]
+fn histogram_bucket(value: i64, width: i64, offset: i64) -> i64 {
+    (value - offset).div_euclid(width) * width + offset
+}
 
-        let size = extract_size(line, &offsets);
-        assert_eq!(size, Some(2326));
+/// Distribute `field`'s values into fixed-width buckets and return
+/// `(bucket_lower_bound, count)` pairs, sorted and with every empty
+/// bucket between the observed min and max filled in with a zero count —
+/// so the result is a contiguous series a caller can plot directly
+/// without having to know which buckets were actually observed.
+pub fn histogram_aggregate(
+    path: &Path,
+    field: HistogramField,
+    bucket_width: i64,
+    offset: i64,
+    status_filter: Option<StatusFilter>,
+    text_pattern: Option<&[u8]>,
+) -> Result<Vec<(i64, u64)>, ParseError> {
+    if bucket_width <= 0 {
+        return Err(ParseError::ParseFailed("bucket_width must be positive".to_string()));
     }
 
-    #[test]
-    fn test_extract_method_path() {
-        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"POST /api/users HTTP/1.1\" 201 100";
-        let offsets = find_fields(line).expect("Should parse");
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
 
-        assert_eq!(extract_method(line, &offsets), b"POST");
-        assert_eq!(extract_path(line, &offsets), b"/api/users");
-    }
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+    let text_finder = text_pattern.map(memchr::memmem::Finder::new);
 
-    #[test]
-    fn test_status_filter_parse() {
-        assert!(matches!(
-            StatusFilter::parse(">=400"),
-            Some(StatusFilter::GreaterOrEqual(400))
-        ));
-        assert!(matches!(
-            StatusFilter::parse("=200"),
-            Some(StatusFilter::Equal(200))
-        ));
-        assert!(matches!(
-            StatusFilter::parse("4xx"),
-            Some(StatusFilter::Range(400, 499))
-        ));
-        assert!(matches!(
-            StatusFilter::parse("500"),
-            Some(StatusFilter::Equal(500))
-        ));
-    }
+    let local_results: Vec<HashMap<i64, u64>> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut counts: HashMap<i64, u64> = HashMap::new();
+            let mut pos = 0;
 
-    #[test]
-    fn test_no_false_positives() {
-        // Valid line with 404 in URL path but status 200
-        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /error/404/page HTTP/1.1\" 200 100";
-        let offsets = find_fields(line).expect("Should parse");
-        let status = extract_status(line, &offsets);
-        assert_eq!(status, Some(200)); // Should be 200, not 404
-    }
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
 
-    #[test]
-    fn test_extract_size() {
-        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /index.html HTTP/1.1\" 200 12345";
-        let offsets = find_fields(line).expect("Should parse");
-        let size = extract_size(line, &offsets);
-        assert_eq!(size, Some(12345));
+                if let Some(offsets) = find_fields(line) {
+                    let mut matches = true;
+
+                    if let Some(ref filter) = status_filter {
+                        match extract_status(line, &offsets) {
+                            Some(status) if filter.matches(status) => {}
+                            _ => matches = false,
+                        }
+                    }
+
+                    if matches {
+                        if let Some(ref finder) = text_finder {
+                            if finder.find(line).is_none() {
+                                matches = false;
+                            }
+                        }
+                    }
+
+                    if matches {
+                        let value = match field {
+                            HistogramField::Size => extract_size(line, &offsets),
+                            HistogramField::Status => extract_status(line, &offsets).map(|s| s as i64),
+                        };
+
+                        if let Some(value) = value {
+                            let bucket = histogram_bucket(value, bucket_width, offset);
+                            *counts.entry(bucket).or_insert(0) += 1;
+                        }
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            counts
+        })
+        .collect();
+
+    let mut global_counts: HashMap<i64, u64> = HashMap::new();
+    for local in local_results {
+        for (bucket, count) in local {
+            *global_counts.entry(bucket).or_insert(0) += count;
+        }
+    }
+
+    Ok(fill_histogram_gaps(global_counts, bucket_width))
+}
+
+/// Like [`histogram_aggregate`], but across multiple files — each file's
+/// bucket counts are summed before the gap-fill pass, same as
+/// [`aggregate_size_multi`].
+pub fn histogram_aggregate_multi(
+    paths: &[&Path],
+    field: HistogramField,
+    bucket_width: i64,
+    offset: i64,
+    status_filter: Option<StatusFilter>,
+    text_pattern: Option<&[u8]>,
+) -> Result<Vec<(i64, u64)>, ParseError> {
+    if bucket_width <= 0 {
+        return Err(ParseError::ParseFailed("bucket_width must be positive".to_string()));
+    }
+
+    let per_file: Result<Vec<Vec<(i64, u64)>>, ParseError> = paths
+        .par_iter()
+        .map(|path| histogram_aggregate(path, field, bucket_width, offset, status_filter, text_pattern))
+        .collect();
+
+    let mut global_counts: HashMap<i64, u64> = HashMap::new();
+    for series in per_file? {
+        for (bucket, count) in series {
+            *global_counts.entry(bucket).or_insert(0) += count;
+        }
+    }
+
+    Ok(fill_histogram_gaps(global_counts, bucket_width))
+}
+
+/// Sort a bucket->count map and fill in every empty bucket between the
+/// observed min and max with a zero count, so the series is contiguous.
+fn fill_histogram_gaps(counts: HashMap<i64, u64>, bucket_width: i64) -> Vec<(i64, u64)> {
+    if counts.is_empty() {
+        return Vec::new();
+    }
+
+    let min_bucket = *counts.keys().min().unwrap();
+    let max_bucket = *counts.keys().max().unwrap();
+
+    let mut result = Vec::new();
+    let mut bucket = min_bucket;
+    while bucket <= max_bucket {
+        result.push((bucket, counts.get(&bucket).copied().unwrap_or(0)));
+        bucket += bucket_width;
+    }
+
+    result
+}
+
+/// Does `value` fall in the half-open `[from, to)` interval, where `None`
+/// on either end means unbounded in that direction?
+#[inline]
+fn in_range(value: i64, from: Option<i64>, to: Option<i64>) -> bool {
+    let above_from = match from {
+        Some(f) => value >= f,
+        None => true,
+    };
+    let below_to = match to {
+        Some(t) => value < t,
+        None => true,
+    };
+    above_from && below_to
+}
+
+/// Accumulate a full [`AggResult`] (sum/count/min/max) per user-defined
+/// range, keyed by range index. Ranges are tested independently per
+/// value — they may overlap, so a value can land in more than one
+/// range's stats — rather than assuming a partition. Complements
+/// [`histogram_aggregate`]'s fixed-width buckets for reports like "bytes
+/// served for 1xx/2xx/3xx/4xx/5xx".
+pub fn range_aggregate(
+    path: &Path,
+    field: HistogramField,
+    ranges: &[(Option<i64>, Option<i64>)],
+    status_filter: Option<StatusFilter>,
+    text_pattern: Option<&[u8]>,
+) -> Result<Vec<AggResult>, ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+    let text_finder = text_pattern.map(memchr::memmem::Finder::new);
+
+    let local_results: Vec<Vec<AggResult>> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut aggs: Vec<AggResult> = (0..ranges.len()).map(|_| AggResult::new()).collect();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                if let Some(offsets) = find_fields(line) {
+                    let mut matches = true;
+
+                    if let Some(ref filter) = status_filter {
+                        match extract_status(line, &offsets) {
+                            Some(status) if filter.matches(status) => {}
+                            _ => matches = false,
+                        }
+                    }
+
+                    if matches {
+                        if let Some(ref finder) = text_finder {
+                            if finder.find(line).is_none() {
+                                matches = false;
+                            }
+                        }
+                    }
+
+                    if matches {
+                        let value = match field {
+                            HistogramField::Size => extract_size(line, &offsets),
+                            HistogramField::Status => extract_status(line, &offsets).map(|s| s as i64),
+                        };
+
+                        if let Some(value) = value {
+                            for (i, &(from, to)) in ranges.iter().enumerate() {
+                                if in_range(value, from, to) {
+                                    let agg = &mut aggs[i];
+                                    agg.sum += value;
+                                    agg.count += 1;
+                                    agg.min = agg.min.min(value);
+                                    agg.max = agg.max.max(value);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            aggs
+        })
+        .collect();
+
+    let mut global_aggs: Vec<AggResult> = (0..ranges.len()).map(|_| AggResult::new()).collect();
+    for local in local_results {
+        for (i, agg) in local.into_iter().enumerate() {
+            global_aggs[i].merge(&agg);
+        }
+    }
+
+    Ok(global_aggs)
+}
+
+/// Like [`range_aggregate`], but across multiple files.
+pub fn range_aggregate_multi(
+    paths: &[&Path],
+    field: HistogramField,
+    ranges: &[(Option<i64>, Option<i64>)],
+    status_filter: Option<StatusFilter>,
+    text_pattern: Option<&[u8]>,
+) -> Result<Vec<AggResult>, ParseError> {
+    let per_file: Result<Vec<Vec<AggResult>>, ParseError> = paths
+        .par_iter()
+        .map(|path| range_aggregate(path, field, ranges, status_filter, text_pattern))
+        .collect();
+
+    let mut global_aggs: Vec<AggResult> = (0..ranges.len()).map(|_| AggResult::new()).collect();
+    for local in per_file? {
+        for (i, agg) in local.into_iter().enumerate() {
+            global_aggs[i].merge(&agg);
+        }
+    }
+
+    Ok(global_aggs)
+}
+
+// ============================================================================
+// INCREMENTAL / DISTRIBUTED AGGREGATION
+// ============================================================================
+
+/// Pre-finalization state of an aggregation, kept in the same shape the
+/// scan already builds it in (before the final sort/gap-fill pass) so it
+/// can be serialized, persisted, merged with another machine's partial
+/// result, or resumed against newly-arrived log files — all without ever
+/// going through an already-averaged value. `avg` is only ever computed
+/// from `sum`/`count` at [`IntermediateAgg::finalize`] time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IntermediateAgg {
+    /// Raw per-key counts, as built by [`group_by_count`].
+    GroupByCount(HashMap<String, u64>),
+    /// Per-key sum/count/min/max, as built by [`aggregate_size`]. Already
+    /// mergeable as-is since [`AggResult`] keeps sum and count separate.
+    SizeAgg(HashMap<String, AggResult>),
+    /// Bucket lower-bound -> count, as built by [`histogram_aggregate`].
+    /// `bucket_width` travels with the counts so [`IntermediateAgg::finalize`]
+    /// can gap-fill without the caller having to remember it separately.
+    Histogram { counts: HashMap<i64, u64>, bucket_width: i64 },
+}
+
+impl IntermediateAgg {
+    /// Fold `other`'s counts into `self` in place. Both sides must be the
+    /// same variant (produced by the same `*_partial` function) — merging
+    /// a group-by-count against a histogram is a caller bug, not a data
+    /// condition, so it's reported as an error rather than silently
+    /// dropped.
+    pub fn merge(&mut self, other: &IntermediateAgg) -> Result<(), ParseError> {
+        match (self, other) {
+            (IntermediateAgg::GroupByCount(a), IntermediateAgg::GroupByCount(b)) => {
+                for (key, count) in b {
+                    *a.entry(key.clone()).or_insert(0) += count;
+                }
+                Ok(())
+            }
+            (IntermediateAgg::SizeAgg(a), IntermediateAgg::SizeAgg(b)) => {
+                for (key, agg) in b {
+                    a.entry(key.clone()).or_insert_with(AggResult::new).merge(agg);
+                }
+                Ok(())
+            }
+            (IntermediateAgg::Histogram { counts: a, .. }, IntermediateAgg::Histogram { counts: b, .. }) => {
+                for (bucket, count) in b {
+                    *a.entry(*bucket).or_insert(0) += count;
+                }
+                Ok(())
+            }
+            _ => Err(ParseError::ParseFailed(
+                "cannot merge IntermediateAgg values of different kinds".to_string(),
+            )),
+        }
+    }
+
+    /// Produce the user-facing result, computing derived values (the
+    /// sorted order for a group-by, the gap-filled series for a
+    /// histogram) only now rather than baking them into the mergeable
+    /// state.
+    pub fn finalize(self) -> FinalizedAgg {
+        match self {
+            IntermediateAgg::GroupByCount(map) => {
+                let mut result: Vec<(String, u64)> = map.into_iter().collect();
+                result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                FinalizedAgg::GroupByCount(result)
+            }
+            IntermediateAgg::SizeAgg(map) => FinalizedAgg::SizeAgg(map),
+            IntermediateAgg::Histogram { counts, bucket_width } => {
+                FinalizedAgg::Histogram(fill_histogram_gaps(counts, bucket_width))
+            }
+        }
+    }
+}
+
+/// The finalized, ready-to-display counterpart of each [`IntermediateAgg`]
+/// variant.
+#[derive(Debug, Clone)]
+pub enum FinalizedAgg {
+    GroupByCount(Vec<(String, u64)>),
+    SizeAgg(HashMap<String, AggResult>),
+    Histogram(Vec<(i64, u64)>),
+}
+
+/// Like [`group_by_count`], but returns the raw per-key counts before the
+/// final sort, so they can be persisted or merged with another partial
+/// result instead of being treated as a finished answer.
+pub fn group_by_count_partial(
+    path: &Path,
+    column: GroupByColumn,
+    predicate: &Predicate,
+    path_matcher: Option<&PathMatcher>,
+) -> Result<IntermediateAgg, ParseError> {
+    let counts = group_by_count(path, column, predicate, path_matcher)?;
+    Ok(IntermediateAgg::GroupByCount(counts.into_iter().collect()))
+}
+
+/// Like [`aggregate_size`], but wraps the result as an [`IntermediateAgg`]
+/// for merging across files or machines. [`aggregate_size`]'s output is
+/// already in mergeable form (sum/count kept apart rather than averaged),
+/// so this is a thin wrapper rather than a second scan.
+pub fn aggregate_size_partial(
+    path: &Path,
+    group_by: Option<GroupByColumn>,
+    status_filter: Option<StatusFilter>,
+    text_pattern: Option<&[u8]>,
+    path_matcher: Option<&PathMatcher>,
+) -> Result<IntermediateAgg, ParseError> {
+    let aggs = aggregate_size(path, group_by, status_filter, text_pattern, path_matcher)?;
+    Ok(IntermediateAgg::SizeAgg(aggs))
+}
+
+/// Like [`histogram_aggregate`], but returns the raw bucket counts before
+/// the gap-fill pass, so partial histograms from different files can be
+/// merged before the series is made contiguous.
+pub fn histogram_aggregate_partial(
+    path: &Path,
+    field: HistogramField,
+    bucket_width: i64,
+    offset: i64,
+    status_filter: Option<StatusFilter>,
+    text_pattern: Option<&[u8]>,
+) -> Result<IntermediateAgg, ParseError> {
+    if bucket_width <= 0 {
+        return Err(ParseError::ParseFailed("bucket_width must be positive".to_string()));
+    }
+
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+    let text_finder = text_pattern.map(memchr::memmem::Finder::new);
+
+    let local_results: Vec<HashMap<i64, u64>> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut counts: HashMap<i64, u64> = HashMap::new();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                if let Some(offsets) = find_fields(line) {
+                    let mut matches = true;
+
+                    if let Some(ref filter) = status_filter {
+                        match extract_status(line, &offsets) {
+                            Some(status) if filter.matches(status) => {}
+                            _ => matches = false,
+                        }
+                    }
+
+                    if matches {
+                        if let Some(ref finder) = text_finder {
+                            if finder.find(line).is_none() {
+                                matches = false;
+                            }
+                        }
+                    }
+
+                    if matches {
+                        let value = match field {
+                            HistogramField::Size => extract_size(line, &offsets),
+                            HistogramField::Status => extract_status(line, &offsets).map(|s| s as i64),
+                        };
+
+                        if let Some(value) = value {
+                            let bucket = histogram_bucket(value, bucket_width, offset);
+                            *counts.entry(bucket).or_insert(0) += 1;
+                        }
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            counts
+        })
+        .collect();
+
+    let mut global_counts: HashMap<i64, u64> = HashMap::new();
+    for local in local_results {
+        for (bucket, count) in local {
+            *global_counts.entry(bucket).or_insert(0) += count;
+        }
+    }
+
+    Ok(IntermediateAgg::Histogram { counts: global_counts, bucket_width })
+}
+
+// ============================================================================
+// SESSION CORRELATION (stitch related lines into transactions)
+// ============================================================================
+
+/// How to key lines together for [`correlate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrelationKey {
+    /// Group by `(ip, user_agent)` — the default, since a shared NAT/proxy
+    /// IP can still be disambiguated into distinct clients by user agent.
+    IpAndUserAgent,
+    /// Group by IP alone.
+    IpOnly,
+}
+
+impl CorrelationKey {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "ip" => Some(CorrelationKey::IpOnly),
+            "ip_user_agent" | "ip_ua" | "ip+ua" => Some(CorrelationKey::IpAndUserAgent),
+            _ => None,
+        }
+    }
+}
+
+/// Separates the IP and user-agent halves of an [`CorrelationKey::IpAndUserAgent`]
+/// key; neither field can legitimately contain a NUL byte.
+const CORRELATION_KEY_SEP: u8 = 0;
+
+/// One request's worth of data needed to build/merge sessions, pulled out
+/// of a line up front so each chunk only has to run [`find_fields`] once.
+#[derive(Debug, Clone)]
+struct SessionEvent {
+    timestamp: i64,
+    size: i64,
+    path: Vec<u8>,
+    status: u16,
+}
+
+fn correlation_key_bytes(line: &[u8], offsets: &FieldOffsets, key_kind: CorrelationKey) -> Vec<u8> {
+    match key_kind {
+        CorrelationKey::IpOnly => extract_ip(line, offsets).to_vec(),
+        CorrelationKey::IpAndUserAgent => {
+            let mut key = extract_ip(line, offsets).to_vec();
+            key.push(CORRELATION_KEY_SEP);
+            key.extend_from_slice(extract_user_agent(line, offsets));
+            key
+        }
+    }
+}
+
+/// A reconstructed burst of activity from one correlation key: every
+/// request whose gap from the previous one (by normalized epoch
+/// timestamp) didn't exceed the configured threshold.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub key: String,
+    pub start: i64,
+    pub end: i64,
+    pub request_count: u64,
+    pub total_bytes: i64,
+    pub distinct_paths: Vec<String>,
+    pub status_histogram: HashMap<u16, u64>,
+}
+
+fn build_session(key: &str, events: &[&SessionEvent]) -> Session {
+    let mut distinct_paths: Vec<String> = Vec::new();
+    let mut seen_paths: std::collections::HashSet<&[u8]> = std::collections::HashSet::new();
+    let mut status_histogram: HashMap<u16, u64> = HashMap::new();
+    let mut total_bytes: i64 = 0;
+
+    for event in events {
+        if seen_paths.insert(event.path.as_slice()) {
+            distinct_paths.push(String::from_utf8_lossy(&event.path).to_string());
+        }
+        *status_histogram.entry(event.status).or_insert(0) += 1;
+        total_bytes += event.size;
+    }
+
+    Session {
+        key: key.to_string(),
+        start: events.first().map(|e| e.timestamp).unwrap_or(0),
+        end: events.last().map(|e| e.timestamp).unwrap_or(0),
+        request_count: events.len() as u64,
+        total_bytes,
+        distinct_paths,
+        status_histogram,
+    }
+}
+
+/// Group lines in `path` into [`Session`]s: events sharing a correlation
+/// key are sorted by (normalized UTC epoch) time and split into separate
+/// sessions whenever the gap between consecutive requests exceeds
+/// `gap_seconds`. The scan is chunked in parallel, so each chunk first
+/// produces a partial per-key event list; a final reduce concatenates
+/// same-key events across all chunks and only *then* sorts and
+/// gap-splits, so a session spanning a 4MB chunk boundary isn't torn in
+/// two.
+pub fn correlate(path: &Path, key_kind: CorrelationKey, gap_seconds: i64) -> Result<Vec<Session>, ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    let local_results: Vec<HashMap<Vec<u8>, Vec<SessionEvent>>> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut events: HashMap<Vec<u8>, Vec<SessionEvent>> = HashMap::new();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                if let Some(offsets) = find_fields(line) {
+                    if let Some(timestamp) = parse_timestamp_to_i64(extract_timestamp(line, &offsets)) {
+                        let key = correlation_key_bytes(line, &offsets, key_kind);
+                        events.entry(key).or_default().push(SessionEvent {
+                            timestamp,
+                            size: extract_size(line, &offsets).unwrap_or(0),
+                            path: extract_path(line, &offsets).to_vec(),
+                            status: extract_status(line, &offsets).unwrap_or(0),
+                        });
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            events
+        })
+        .collect();
+
+    // Merge: concatenate every chunk's events per key before sorting, so
+    // gap-splitting sees the whole key's timeline rather than one chunk's slice.
+    let mut merged: HashMap<Vec<u8>, Vec<SessionEvent>> = HashMap::new();
+    for local in local_results {
+        for (key, mut chunk_events) in local {
+            merged.entry(key).or_default().append(&mut chunk_events);
+        }
+    }
+
+    let mut sessions = Vec::new();
+    for (key, mut events) in merged {
+        events.sort_by_key(|e| e.timestamp);
+
+        let key_str = std::str::from_utf8(&key)
+            .map(|s| s.replace('\u{0}', " | "))
+            .unwrap_or_else(|_| "???".to_string());
+
+        let mut current: Vec<&SessionEvent> = Vec::new();
+        for event in &events {
+            if let Some(last) = current.last() {
+                if event.timestamp - last.timestamp > gap_seconds {
+                    sessions.push(build_session(&key_str, &current));
+                    current.clear();
+                }
+            }
+            current.push(event);
+        }
+        if !current.is_empty() {
+            sessions.push(build_session(&key_str, &current));
+        }
+    }
+
+    Ok(sessions)
+}
+
+// ============================================================================
+// MALFORMED-LINE DIAGNOSTICS
+// ============================================================================
+
+/// A rejected line recorded by [`scan_with_diagnostics`]: why it was
+/// rejected, where, and a copy of the offending line for inspection.
+#[derive(Debug, Clone)]
+pub struct RejectedLine {
+    pub fault: ParseFaultKind,
+    pub offset: usize,
+    pub line: String,
+}
+
+/// Result of a diagnostics scan: how many lines parsed vs. were rejected,
+/// a per-fault tally, and a capped sample of the rejected lines
+/// themselves — so a surprisingly low count can be explained as "wrong
+/// LogFormat" (most/all lines rejected, one dominant fault kind) versus
+/// "a few corrupt lines" (rejected count is small relative to parsed).
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticsReport {
+    pub parsed_count: usize,
+    pub rejected_count: usize,
+    pub fault_tally: HashMap<ParseFaultKind, usize>,
+    pub sample: Vec<RejectedLine>,
+}
+
+/// Scan `path` with [`find_fields_diag`] instead of the normal
+/// [`find_fields`], keeping a per-fault tally and a capped sample of
+/// rejected lines alongside the parsed/rejected counts. Like the other
+/// scan functions the work is chunked in parallel; each chunk produces a
+/// partial report which is then merged (tallies summed, samples
+/// concatenated up to `sample_limit`) rather than reduced by any single
+/// chunk alone.
+pub fn scan_with_diagnostics(path: &Path, sample_limit: usize) -> Result<DiagnosticsReport, ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    let local_results: Vec<DiagnosticsReport> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut report = DiagnosticsReport::default();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                if !line.is_empty() {
+                    match find_fields_diag(line) {
+                        Ok(_) => report.parsed_count += 1,
+                        Err(fault) => {
+                            report.rejected_count += 1;
+                            *report.fault_tally.entry(fault.kind).or_insert(0) += 1;
+                            if report.sample.len() < sample_limit {
+                                report.sample.push(RejectedLine {
+                                    fault: fault.kind,
+                                    offset: fault.offset,
+                                    line: String::from_utf8_lossy(line).to_string(),
+                                });
+                            }
+                        }
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            report
+        })
+        .collect();
+
+    let mut merged = DiagnosticsReport::default();
+    for local in local_results {
+        merged.parsed_count += local.parsed_count;
+        merged.rejected_count += local.rejected_count;
+        for (kind, count) in local.fault_tally {
+            *merged.fault_tally.entry(kind).or_insert(0) += count;
+        }
+        for rejected in local.sample {
+            if merged.sample.len() < sample_limit {
+                merged.sample.push(rejected);
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_fields() {
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /index.html HTTP/1.1\" 200 2326";
+        let offsets = find_fields(line).expect("Should parse");
+
+        assert_eq!(&line[..offsets.ip_end], b"192.168.1.1");
+        assert_eq!(
+            &line[offsets.request_start..offsets.request_end],
+            b"GET /index.html HTTP/1.1"
+        );
+
+        let status = extract_status(line, &offsets);
+        assert_eq!(status, Some(200));
+
+        let size = extract_size(line, &offsets);
+        assert_eq!(size, Some(2326));
+    }
+
+    #[test]
+    fn test_extract_method_path() {
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"POST /api/users HTTP/1.1\" 201 100";
+        let offsets = find_fields(line).expect("Should parse");
+
+        assert_eq!(extract_method(line, &offsets), b"POST");
+        assert_eq!(extract_path(line, &offsets), b"/api/users");
+    }
+
+    #[test]
+    fn test_status_filter_parse() {
+        assert!(matches!(
+            StatusFilter::parse(">=400"),
+            Some(StatusFilter::GreaterOrEqual(400))
+        ));
+        assert!(matches!(
+            StatusFilter::parse("=200"),
+            Some(StatusFilter::Equal(200))
+        ));
+        assert!(matches!(
+            StatusFilter::parse("4xx"),
+            Some(StatusFilter::Range(400, 499))
+        ));
+        assert!(matches!(
+            StatusFilter::parse("500"),
+            Some(StatusFilter::Equal(500))
+        ));
+    }
+
+    #[test]
+    fn test_no_false_positives() {
+        // Valid line with 404 in URL path but status 200
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /error/404/page HTTP/1.1\" 200 100";
+        let offsets = find_fields(line).expect("Should parse");
+        let status = extract_status(line, &offsets);
+        assert_eq!(status, Some(200)); // Should be 200, not 404
+    }
+
+    #[test]
+    fn test_extract_size() {
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /index.html HTTP/1.1\" 200 12345";
+        let offsets = find_fields(line).expect("Should parse");
+        let size = extract_size(line, &offsets);
+        assert_eq!(size, Some(12345));
+    }
+
+    fn write_temp_log(data: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "apache_simd_test_{}_{}.log",
+            std::process::id(),
+            data.len()
+        ));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_regex_search_multi_tags_every_matching_pattern() {
+        let data = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /index.html HTTP/1.1\" 200 100\n\
+192.168.1.2 - - [10/Oct/2024:13:55:37 +0000] \"GET /admin?id=1 UNION SELECT 1 HTTP/1.1\" 403 0\n\
+192.168.1.3 - - [10/Oct/2024:13:55:38 +0000] \"GET /etc/passwd HTTP/1.1\" 403 0\n";
+        let tmp_path = write_temp_log(data);
+
+        let patterns = vec![
+            r"UNION\s+SELECT".to_string(),
+            r"403".to_string(),
+        ];
+        let (count, samples) = regex_search_multi(&tmp_path, &patterns, None, 10, None).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        assert_eq!(count, 2);
+        let union_line = samples.iter().find(|m| m.line.contains("UNION")).unwrap();
+        assert_eq!(union_line.labels.len(), 2);
+        let passwd_line = samples.iter().find(|m| m.line.contains("passwd")).unwrap();
+        assert_eq!(passwd_line.labels, vec!["1".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_predicate_and_or_not_with_parens() {
+        let pred = parse_predicate(r#"status:>=500 AND (path~"^/api" OR NOT ua~"bot")"#).unwrap();
+
+        let admin_request = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /api/users HTTP/1.1\" 500 10 \"-\" \"curl/7.68.0\"";
+        assert!(predicate_matches(&pred, admin_request));
+
+        let bot_request = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /about HTTP/1.1\" 500 10 \"-\" \"evilbot/1.0\"";
+        assert!(!predicate_matches(&pred, bot_request));
+
+        let ok_request = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /api/users HTTP/1.1\" 200 10 \"-\" \"curl/7.68.0\"";
+        assert!(!predicate_matches(&pred, ok_request));
+    }
+
+    #[test]
+    fn test_parse_predicate_field_equals() {
+        let pred = parse_predicate(r#"method:"POST""#).unwrap();
+        let post_line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"POST /api/users HTTP/1.1\" 201 10";
+        let get_line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /api/users HTTP/1.1\" 200 10";
+        assert!(predicate_matches(&pred, post_line));
+        assert!(!predicate_matches(&pred, get_line));
+    }
+
+    #[test]
+    fn test_parse_predicate_rejects_unknown_field() {
+        assert!(parse_predicate("bogus_field:\"x\"").is_ok()); // falls back to plain text
+        assert!(parse_predicate("bogus_field~\"x\"").is_err()); // regex form requires a known field
+    }
+
+    #[test]
+    fn test_predicate_all_matches_everything() {
+        let line = b"not even close to a valid apache line";
+        assert!(predicate_matches(&Predicate::All, line));
+    }
+
+    #[test]
+    fn test_days_from_civil_known_epoch_points() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017); // first day after the 2000 leap day
+        assert_eq!(days_from_civil(2024, 2, 29), 19782); // 2024 is a leap year
+    }
+
+    #[test]
+    fn test_parse_timestamp_to_i64_normalizes_offset_to_utc() {
+        // Same wall-clock time, five time zones apart, should be five
+        // hours apart on the epoch axis.
+        let utc = parse_timestamp_to_i64(b"16/Dec/2025:11:26:41 +0000").unwrap();
+        let east = parse_timestamp_to_i64(b"16/Dec/2025:11:26:41 +0500").unwrap();
+        let west = parse_timestamp_to_i64(b"16/Dec/2025:11:26:41 -0500").unwrap();
+
+        assert_eq!(utc - east, 5 * 3600);
+        assert_eq!(west - utc, 5 * 3600);
+    }
+
+    #[test]
+    fn test_parse_timestamp_to_i64_same_instant_different_offsets_are_equal() {
+        // 11:26:41 -0500 is the same instant as 16:26:41 +0000.
+        let a = parse_timestamp_to_i64(b"16/Dec/2025:11:26:41 -0500").unwrap();
+        let b = parse_timestamp_to_i64(b"16/Dec/2025:16:26:41 +0000").unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_time_filter_matches_across_zones() {
+        // An ISO filter expressed in UTC should include an Apache line
+        // logged at the same instant in a different zone.
+        let filter = TimeFilter::new(Some("2025-12-16T16:00:00Z"), Some("2025-12-16T17:00:00Z")).unwrap();
+        let ts = parse_timestamp_to_i64(b"16/Dec/2025:11:26:41 -0500").unwrap();
+        assert!(filter.matches(ts));
+    }
+
+    #[test]
+    fn test_time_filter_assume_offset_for_bare_iso_input() {
+        // A bare "no offset" ISO timestamp with assume_offset_minutes=-300
+        // (US Eastern, UTC-5) should line up with the equivalent explicit-Z input.
+        let assumed = TimeFilter::new_with_zone(Some("2025-12-16T11:26:41"), None, -300).unwrap();
+        let explicit = TimeFilter::new(Some("2025-12-16T16:26:41Z"), None).unwrap();
+        assert_eq!(assumed.start, explicit.start);
+    }
+
+    #[test]
+    fn test_correlation_key_parse() {
+        assert_eq!(CorrelationKey::parse("ip"), Some(CorrelationKey::IpOnly));
+        assert_eq!(CorrelationKey::parse("ip_ua"), Some(CorrelationKey::IpAndUserAgent));
+        assert_eq!(CorrelationKey::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_build_session_aggregates_distinct_paths_and_status_histogram() {
+        let events = vec![
+            SessionEvent { timestamp: 100, size: 10, path: b"/a".to_vec(), status: 200 },
+            SessionEvent { timestamp: 110, size: 20, path: b"/b".to_vec(), status: 200 },
+            SessionEvent { timestamp: 120, size: 30, path: b"/a".to_vec(), status: 404 },
+        ];
+        let refs: Vec<&SessionEvent> = events.iter().collect();
+        let session = build_session("1.2.3.4", &refs);
+
+        assert_eq!(session.key, "1.2.3.4");
+        assert_eq!(session.start, 100);
+        assert_eq!(session.end, 120);
+        assert_eq!(session.request_count, 3);
+        assert_eq!(session.total_bytes, 60);
+        assert_eq!(session.distinct_paths, vec!["/a".to_string(), "/b".to_string()]);
+        assert_eq!(session.status_histogram.get(&200), Some(&2));
+        assert_eq!(session.status_histogram.get(&404), Some(&1));
+    }
+
+    #[test]
+    fn test_find_fields_diag_reports_short_line() {
+        let fault = find_fields_diag(b"short").unwrap_err();
+        assert_eq!(fault.kind, ParseFaultKind::ShortLine);
+    }
+
+    #[test]
+    fn test_find_fields_diag_reports_unterminated_bracket() {
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000 \"GET / HTTP/1.1\" 200 10";
+        let fault = find_fields_diag(line).unwrap_err();
+        assert_eq!(fault.kind, ParseFaultKind::UnterminatedBracket);
+    }
+
+    #[test]
+    fn test_find_fields_diag_reports_missing_request_quote() {
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] GET / HTTP/1.1 200 10";
+        let fault = find_fields_diag(line).unwrap_err();
+        assert_eq!(fault.kind, ParseFaultKind::MissingRequestQuote);
+    }
+
+    #[test]
+    fn test_find_fields_diag_reports_truncated_status() {
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET / HTTP/1.1\" 2";
+        let fault = find_fields_diag(line).unwrap_err();
+        assert_eq!(fault.kind, ParseFaultKind::TruncatedStatus);
+    }
+
+    #[test]
+    fn test_find_fields_diag_ok_matches_find_fields() {
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /index.html HTTP/1.1\" 200 2326";
+        assert!(find_fields_diag(line).is_ok());
+        assert!(find_fields(line).is_some());
+    }
+
+    #[test]
+    fn test_histogram_bucket_basic_and_negative() {
+        assert_eq!(histogram_bucket(1234, 1000, 0), 1000);
+        assert_eq!(histogram_bucket(999, 1000, 0), 0);
+        assert_eq!(histogram_bucket(-1, 1000, 0), -1000);
+        assert_eq!(histogram_bucket(150, 100, 50), 150);
+        assert_eq!(histogram_bucket(149, 100, 50), 50);
+    }
+
+    #[test]
+    fn test_fill_histogram_gaps_produces_contiguous_series() {
+        let mut counts = HashMap::new();
+        counts.insert(0, 3u64);
+        counts.insert(300, 5u64);
+
+        let series = fill_histogram_gaps(counts, 100);
+        assert_eq!(series, vec![(0, 3), (100, 0), (200, 0), (300, 5)]);
+    }
+
+    #[test]
+    fn test_fill_histogram_gaps_empty_input() {
+        assert_eq!(fill_histogram_gaps(HashMap::new(), 100), Vec::new());
+    }
+
+    #[test]
+    fn test_histogram_field_parse() {
+        assert_eq!(HistogramField::parse("size"), Some(HistogramField::Size));
+        assert_eq!(HistogramField::parse("status_code"), Some(HistogramField::Status));
+        assert_eq!(HistogramField::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_in_range_bounds() {
+        assert!(in_range(500, Some(0), Some(1000)));
+        assert!(!in_range(1000, Some(0), Some(1000))); // half-open: exclusive of `to`
+        assert!(in_range(0, Some(0), Some(1000))); // inclusive of `from`
+        assert!(in_range(-5, None, Some(0)));
+        assert!(in_range(i64::MAX, Some(0), None));
+        assert!(!in_range(-1, Some(0), None));
+    }
+
+    #[test]
+    fn test_in_range_overlapping_ranges_both_match() {
+        // [0, 1000) and [500, 2000) overlap; a value in the overlap
+        // should match both rather than assuming a partition.
+        assert!(in_range(600, Some(0), Some(1000)));
+        assert!(in_range(600, Some(500), Some(2000)));
+    }
+
+    #[test]
+    fn test_intermediate_agg_group_by_count_merge_and_finalize() {
+        let mut a = IntermediateAgg::GroupByCount(HashMap::from([
+            ("GET".to_string(), 3u64),
+            ("POST".to_string(), 1u64),
+        ]));
+        let b = IntermediateAgg::GroupByCount(HashMap::from([
+            ("GET".to_string(), 2u64),
+            ("DELETE".to_string(), 5u64),
+        ]));
+
+        a.merge(&b).unwrap();
+
+        match a.finalize() {
+            FinalizedAgg::GroupByCount(sorted) => {
+                assert_eq!(sorted[0], ("DELETE".to_string(), 5));
+                assert_eq!(sorted[1], ("GET".to_string(), 5));
+                assert_eq!(sorted[2], ("POST".to_string(), 1));
+            }
+            other => panic!("expected GroupByCount, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_intermediate_agg_size_agg_merge_keeps_sum_and_count_separate() {
+        let mut first = AggResult::new();
+        first.sum = 100;
+        first.count = 2;
+        first.min = 30;
+        first.max = 70;
+        let mut second = AggResult::new();
+        second.sum = 50;
+        second.count = 1;
+        second.min = 50;
+        second.max = 50;
+
+        let mut a = IntermediateAgg::SizeAgg(HashMap::from([("/index.html".to_string(), first)]));
+        let b = IntermediateAgg::SizeAgg(HashMap::from([("/index.html".to_string(), second)]));
+        a.merge(&b).unwrap();
+
+        match a.finalize() {
+            FinalizedAgg::SizeAgg(map) => {
+                let agg = &map["/index.html"];
+                assert_eq!(agg.sum, 150);
+                assert_eq!(agg.count, 3);
+                assert_eq!(agg.min, 30);
+                assert_eq!(agg.max, 70);
+                assert!((agg.avg() - 50.0).abs() < f64::EPSILON);
+            }
+            other => panic!("expected SizeAgg, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_intermediate_agg_histogram_merge_and_gap_fill() {
+        let mut a = IntermediateAgg::Histogram {
+            counts: HashMap::from([(0i64, 2u64)]),
+            bucket_width: 100,
+        };
+        let b = IntermediateAgg::Histogram {
+            counts: HashMap::from([(0i64, 1u64), (200i64, 3u64)]),
+            bucket_width: 100,
+        };
+        a.merge(&b).unwrap();
+
+        match a.finalize() {
+            FinalizedAgg::Histogram(series) => {
+                assert_eq!(series, vec![(0, 3), (100, 0), (200, 3)]);
+            }
+            other => panic!("expected Histogram, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_intermediate_agg_merge_rejects_mismatched_variants() {
+        let mut a = IntermediateAgg::GroupByCount(HashMap::new());
+        let b = IntermediateAgg::Histogram { counts: HashMap::new(), bucket_width: 10 };
+        assert!(a.merge(&b).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_size_partial_roundtrips_through_serde_json() {
+        let partial = IntermediateAgg::SizeAgg(HashMap::from([("_total".to_string(), AggResult::new())]));
+        let json = serde_json::to_string(&partial).expect("serialize");
+        let restored: IntermediateAgg = serde_json::from_str(&json).expect("deserialize");
+        match restored {
+            IntermediateAgg::SizeAgg(map) => assert!(map.contains_key("_total")),
+            other => panic!("expected SizeAgg, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_path_matcher_empty_include_matches_everything() {
+        let matcher = PathMatcher::new(&[], &[]).unwrap();
+        assert!(matcher.matches(b"/anything/at/all"));
+    }
+
+    #[test]
+    fn test_path_matcher_include_glob_scopes_to_prefix() {
+        let matcher = PathMatcher::new(&["/api/*".to_string()], &[]).unwrap();
+        assert!(matcher.matches(b"/api/orders/123"));
+        assert!(!matcher.matches(b"/static/app.js"));
+    }
+
+    #[test]
+    fn test_path_matcher_exclude_narrows_an_include_match() {
+        // "count 5xx under /checkout but not /checkout/health"
+        let matcher = PathMatcher::new(
+            &["/checkout*".to_string()],
+            &["/checkout/health".to_string()],
+        ).unwrap();
+        assert!(matcher.matches(b"/checkout/cart"));
+        assert!(!matcher.matches(b"/checkout/health"));
+    }
+
+    #[test]
+    fn test_glob_to_path_regex_escapes_regex_metacharacters() {
+        // A literal '.' in a glob (e.g. a file extension) must not act as
+        // a regex wildcard.
+        let regex = glob_to_path_regex("/report.v1");
+        let compiled = Regex::new(&regex).unwrap();
+        assert!(compiled.is_match(b"/report.v1"));
+        assert!(!compiled.is_match(b"/reportXv1"));
+    }
+
+    #[test]
+    fn test_group_by_regex_groups_by_single_named_capture() {
+        let data = b"event=login user=alice\nevent=login user=bob\nevent=logout user=alice\n";
+        let tmp_path = write_temp_log(data);
+
+        let result = group_by_regex(
+            &tmp_path,
+            r"event=(?P<event>\w+)",
+            &Predicate::All,
+            None,
+        ).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        let as_map: HashMap<String, u64> = result.into_iter().collect();
+        assert_eq!(as_map.get("login"), Some(&2));
+        assert_eq!(as_map.get("logout"), Some(&1));
+    }
+
+    #[test]
+    fn test_group_by_regex_composite_key_from_multiple_captures() {
+        let data = b"event=login user=alice\nevent=login user=bob\nevent=login user=alice\n";
+        let tmp_path = write_temp_log(data);
+
+        let result = group_by_regex(
+            &tmp_path,
+            r"event=(?P<event>\w+) user=(?P<user>\w+)",
+            &Predicate::All,
+            None,
+        ).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        let as_map: HashMap<String, u64> = result.into_iter().collect();
+        assert_eq!(as_map.get("login|alice"), Some(&2));
+        assert_eq!(as_map.get("login|bob"), Some(&1));
+    }
+
+    #[test]
+    fn test_group_by_regex_requires_named_capture_group() {
+        let tmp_path = write_temp_log(b"event=login user=alice\n");
+        let result = group_by_regex(&tmp_path, r"event=(\w+)", &Predicate::All, None);
+        std::fs::remove_file(&tmp_path).ok();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_group_by_regex_skips_non_matching_lines() {
+        let data = b"event=login user=alice\nmalformed line with no fields\n";
+        let tmp_path = write_temp_log(data);
+
+        let result = group_by_regex(
+            &tmp_path,
+            r"event=(?P<event>\w+)",
+            &Predicate::All,
+            None,
+        ).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        assert_eq!(result, vec![("login".to_string(), 1)]);
     }
 }
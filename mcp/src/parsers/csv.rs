@@ -5,16 +5,10 @@ use super::ParseError;
 /// CSV/TSV parser using Polars native reader
 
 pub fn parse(path: &Path) -> Result<LazyFrame, ParseError> {
-    // Detect delimiter by examining first line
-    let first_line = std::fs::read_to_string(path)
-        .map(|s| s.lines().next().unwrap_or("").to_string())
-        .unwrap_or_default();
-
-    let separator = if first_line.contains('\t') {
-        b'\t'
-    } else {
-        b','
-    };
+    // Detect delimiter from a sample of lines, rather than a single one,
+    // so a header/row with an unrepresentative delimiter count doesn't
+    // misroute the whole file.
+    let separator = super::detect::detect_csv_separator(path, super::detect::DEFAULT_SAMPLE_SIZE)?;
 
     let lf = LazyCsvReader::new(path)
         .with_has_header(true)
@@ -0,0 +1,169 @@
+//! Transparent decompression of compressed log archives.
+//!
+//! Rotated/archived logs are almost always compressed (`access.log.1.gz`,
+//! `syslog.2.zst`, `messages.bz2`). This module sniffs the first few bytes
+//! of a file for a known magic number and, if found, streams the
+//! decompressed content into a temp file so the existing `&Path`-based
+//! parsers can run against it unmodified.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use super::ParseError;
+
+/// Disambiguates concurrent `open()` calls (even for the same source path,
+/// e.g. a retry) within one process, on top of the per-path hash below.
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+/// Sniff the first few bytes of `path` against known compression magic
+/// numbers: `1F 8B` (gzip), `28 B5 2F FD` (zstd), `BZh` (bzip2), and
+/// `FD 37 7A 58 5A 00` (xz).
+fn sniff_compression(path: &Path) -> Result<Compression, ParseError> {
+    let mut file = File::open(path)?;
+    let mut magic = [0u8; 6];
+    let n = file.read(&mut magic)?;
+    let magic = &magic[..n];
+
+    if magic.starts_with(&[0x1F, 0x8B]) {
+        Ok(Compression::Gzip)
+    } else if magic.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+        Ok(Compression::Zstd)
+    } else if magic.starts_with(b"BZh") {
+        Ok(Compression::Bzip2)
+    } else if magic.starts_with(&[0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00]) {
+        Ok(Compression::Xz)
+    } else {
+        Ok(Compression::None)
+    }
+}
+
+/// The path that should actually be scanned/parsed, plus (if the source was
+/// compressed) a guard that removes the decompressed temp file on drop.
+pub struct DecompressedSource {
+    path: PathBuf,
+    temp: bool,
+}
+
+impl DecompressedSource {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether `path()` points at a decompressed temp file rather than the
+    /// original source. Callers that build a `LazyFrame` against this path
+    /// must `collect()` it before the guard drops and removes the file.
+    pub fn is_temp(&self) -> bool {
+        self.temp
+    }
+}
+
+impl Drop for DecompressedSource {
+    fn drop(&mut self) {
+        if self.temp {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Open `path`, transparently decompressing it into a temp file first if it
+/// carries a recognized compression magic number. Uncompressed files are
+/// returned as-is with no copy.
+pub fn open(path: &Path) -> Result<DecompressedSource, ParseError> {
+    let compression = sniff_compression(path)?;
+    if compression == Compression::None {
+        return Ok(DecompressedSource { path: path.to_path_buf(), temp: false });
+    }
+
+    let file = File::open(path)?;
+
+    // Key the temp name on a hash of the full source path (not just its
+    // basename, which collides across directories, e.g. two "access.log.1.gz"
+    // under different vhosts) plus a per-process atomic counter, so
+    // concurrent tool calls never race onto the same temp file and one
+    // source's decompressed data can never silently truncate another's.
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    let path_hash = hasher.finish();
+    let counter = TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let temp_path = std::env::temp_dir().join(format!(
+        "forensic_log_mcp_{}_{:x}_{}_{}.tmp",
+        std::process::id(),
+        path_hash,
+        counter,
+        path.file_name().and_then(|n| n.to_str()).unwrap_or("decompressed")
+    ));
+    let mut out = File::create(&temp_path)?;
+
+    match compression {
+        Compression::Gzip => {
+            let mut decoder = flate2::read::GzDecoder::new(file);
+            std::io::copy(&mut decoder, &mut out)?;
+        }
+        Compression::Zstd => {
+            let mut decoder = zstd::Decoder::new(file)?;
+            std::io::copy(&mut decoder, &mut out)?;
+        }
+        Compression::Bzip2 => {
+            let mut decoder = bzip2::read::BzDecoder::new(file);
+            std::io::copy(&mut decoder, &mut out)?;
+        }
+        Compression::Xz => {
+            let mut decoder = xz2::read::XzDecoder::new(file);
+            std::io::copy(&mut decoder, &mut out)?;
+        }
+        Compression::None => unreachable!("handled above"),
+    }
+
+    Ok(DecompressedSource { path: temp_path, temp: true })
+}
+
+/// Strip a trailing compression extension so format detection can see the
+/// inner one, e.g. `access.ndjson.gz` -> `access.ndjson`.
+pub fn strip_compression_ext(path: &Path) -> PathBuf {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()) {
+        Some(ext) if matches!(ext.as_str(), "gz" | "zst" | "zstd" | "bz2" | "xz") => path.with_extension(""),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_compression_ext() {
+        assert_eq!(strip_compression_ext(Path::new("access.ndjson.gz")), Path::new("access.ndjson"));
+        assert_eq!(strip_compression_ext(Path::new("syslog.2.zst")), Path::new("syslog.2"));
+        assert_eq!(strip_compression_ext(Path::new("access.log")), Path::new("access.log"));
+    }
+
+    #[test]
+    fn test_sniff_compression_gzip_magic() {
+        let path = std::env::temp_dir().join(format!("decompress_test_gz_{}.tmp", std::process::id()));
+        std::fs::write(&path, [0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00]).unwrap();
+        let result = sniff_compression(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result, Compression::Gzip);
+    }
+
+    #[test]
+    fn test_sniff_compression_none_for_plain_text() {
+        let path = std::env::temp_dir().join(format!("decompress_test_plain_{}.tmp", std::process::id()));
+        std::fs::write(&path, b"192.168.1.1 - - [10/Oct/2024] \"GET / HTTP/1.1\" 200 100\n").unwrap();
+        let result = sniff_compression(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result, Compression::None);
+    }
+}
@@ -0,0 +1,177 @@
+//! Confidence-scored format detection.
+//!
+//! A single line is too easy to misclassify (a CSV header containing a
+//! quoted space, a leading blank/comment line in an NDJSON file). Instead
+//! this samples the first [`DEFAULT_SAMPLE_SIZE`] non-empty lines, scores
+//! each candidate format independently, and picks whichever cleared
+//! [`CONFIDENCE_THRESHOLD`] on the largest fraction of sampled lines.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use super::ParseError;
+
+pub const DEFAULT_SAMPLE_SIZE: usize = 50;
+pub const CONFIDENCE_THRESHOLD: f64 = 0.6;
+
+const APACHE_PATTERN: &str = r#"^(\S+)\s+(\S+)\s+(\S+)\s+\[([^\]]+)\]\s+"([^"]+)"\s+(\d+)\s+(\S+)(?:\s+"([^"]*)")?(?:\s+"([^"]*)")?$"#;
+const SYSLOG_3164_PATTERN: &str = r"^(?:<(\d+)>)?(\w{3}\s+\d+\s+\d+:\d+:\d+)\s+(\S+)\s+(\S+?)(?:\[(\d+)\])?:\s*(.*)$";
+const SYSLOG_5424_PATTERN: &str = r"^<(\d+)>1\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(.*)$";
+
+/// Fraction of sampled lines (0.0-1.0) that matched each candidate format.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FormatScores {
+    pub json: f64,
+    pub csv: f64,
+    pub apache: f64,
+    pub syslog: f64,
+}
+
+impl FormatScores {
+    /// The format with the highest score, if it cleared `threshold`.
+    fn best_above(&self, threshold: f64) -> Option<super::LogFormat> {
+        let candidates = [
+            (super::LogFormat::Json, self.json),
+            (super::LogFormat::Csv, self.csv),
+            (super::LogFormat::Apache, self.apache),
+            (super::LogFormat::Syslog, self.syslog),
+        ];
+
+        candidates
+            .into_iter()
+            .filter(|(_, score)| *score >= threshold)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(format, _)| format)
+    }
+}
+
+/// Result of [`detect_format_sampled`]: the chosen format (or `Auto` if
+/// nothing cleared the confidence threshold) plus the per-format scores
+/// that produced it, so callers can report detection confidence.
+#[derive(Debug, Clone, Copy)]
+pub struct DetectionResult {
+    pub format: super::LogFormat,
+    pub scores: FormatScores,
+}
+
+/// Read the first `n` non-empty lines of `path`.
+fn sample_lines(path: &Path, n: usize) -> Result<Vec<String>, ParseError> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(content.lines().filter(|l| !l.trim().is_empty()).take(n).map(|l| l.to_string()).collect())
+}
+
+fn is_json_object(line: &str) -> bool {
+    matches!(serde_json::from_str::<serde_json::Value>(line), Ok(serde_json::Value::Object(_)))
+}
+
+/// Score how consistently `lines` split on `sep`: the fraction of lines
+/// whose delimiter count matches the most common (mode) count, which is
+/// only meaningful if that mode count is non-zero.
+fn delimiter_consistency(lines: &[String], sep: char) -> f64 {
+    let counts: Vec<usize> = lines.iter().map(|l| l.matches(sep).count()).collect();
+
+    let mut freq: HashMap<usize, usize> = HashMap::new();
+    for &c in &counts {
+        *freq.entry(c).or_insert(0) += 1;
+    }
+
+    match freq.into_iter().filter(|&(count, _)| count > 0).max_by_key(|&(_, f)| f) {
+        Some((_, mode_freq)) => mode_freq as f64 / lines.len() as f64,
+        None => 0.0,
+    }
+}
+
+/// Pick whichever of `,` or `\t` splits `lines` most consistently, paired
+/// with its consistency score.
+fn best_csv_separator(lines: &[String]) -> (u8, f64) {
+    let comma = delimiter_consistency(lines, ',');
+    let tab = delimiter_consistency(lines, '\t');
+    if tab > comma {
+        (b'\t', tab)
+    } else {
+        (b',', comma)
+    }
+}
+
+/// Score every candidate format against a sample of `path`'s lines and
+/// return the best match above [`CONFIDENCE_THRESHOLD`] (or `Auto` if none
+/// clears it), along with the full score breakdown.
+pub fn detect_format_sampled(path: &Path, sample_size: usize) -> Result<DetectionResult, ParseError> {
+    let lines = sample_lines(path, sample_size)?;
+    if lines.is_empty() {
+        return Ok(DetectionResult { format: super::LogFormat::Auto, scores: FormatScores::default() });
+    }
+
+    let apache_re = Regex::new(APACHE_PATTERN).unwrap();
+    let syslog_3164_re = Regex::new(SYSLOG_3164_PATTERN).unwrap();
+    let syslog_5424_re = Regex::new(SYSLOG_5424_PATTERN).unwrap();
+
+    let n = lines.len() as f64;
+    let json = lines.iter().filter(|l| is_json_object(l)).count() as f64 / n;
+    let apache = lines.iter().filter(|l| apache_re.is_match(l)).count() as f64 / n;
+    let syslog = lines.iter().filter(|l| syslog_3164_re.is_match(l) || syslog_5424_re.is_match(l)).count() as f64 / n;
+    let (_, csv) = best_csv_separator(&lines);
+
+    let scores = FormatScores { json, csv, apache, syslog };
+    let format = scores.best_above(CONFIDENCE_THRESHOLD).unwrap_or(super::LogFormat::Auto);
+
+    Ok(DetectionResult { format, scores })
+}
+
+/// Sample `path` and return the delimiter byte (`,` or `\t`) that splits
+/// its lines most consistently, defaulting to `,` when neither is a clear
+/// winner. Used by the CSV parser instead of looking at a single line.
+pub fn detect_csv_separator(path: &Path, sample_size: usize) -> Result<u8, ParseError> {
+    let lines = sample_lines(path, sample_size)?;
+    if lines.is_empty() {
+        return Ok(b',');
+    }
+    Ok(best_csv_separator(&lines).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("detect_test_{}_{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_detect_format_sampled_json() {
+        let path = write_temp("json.log", "\n{\"a\": 1}\n{\"a\": 2}\n{\"a\": 3}\n");
+        let result = detect_format_sampled(&path, DEFAULT_SAMPLE_SIZE).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result.format, super::super::LogFormat::Json);
+        assert!(result.scores.json > 0.9);
+    }
+
+    #[test]
+    fn test_detect_format_sampled_apache() {
+        let line = "192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /index.html HTTP/1.1\" 200 2326 \"-\" \"Mozilla/5.0\"\n";
+        let content = line.repeat(5);
+        let path = write_temp("access.log", &content);
+        let result = detect_format_sampled(&path, DEFAULT_SAMPLE_SIZE).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result.format, super::super::LogFormat::Apache);
+    }
+
+    #[test]
+    fn test_detect_csv_separator_prefers_tab_when_consistent() {
+        let path = write_temp("data.tsv", "a\tb\tc\n1\t2\t3\n4\t5\t6\n");
+        let sep = detect_csv_separator(&path, DEFAULT_SAMPLE_SIZE).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(sep, b'\t');
+    }
+
+    #[test]
+    fn test_csv_header_with_quoted_space_does_not_misroute_to_apache() {
+        let content = "\"id\", \"full name\", \"note\"\n1, \"Jane Doe\", \"ok\"\n2, \"Jo Public\", \"ok\"\n";
+        let path = write_temp("quoted.csv", content);
+        let result = detect_format_sampled(&path, DEFAULT_SAMPLE_SIZE).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result.format, super::super::LogFormat::Csv);
+    }
+}
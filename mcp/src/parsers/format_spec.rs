@@ -0,0 +1,257 @@
+//! Configurable log-format descriptor.
+//!
+//! [`apache_simd`](super::apache_simd)'s hot scan path hardwires fixed byte
+//! offsets for the Apache combined log format, which is fast but breaks on
+//! nginx logs, vhost-prefixed logs, or any custom `LogFormat` directive.
+//! This module compiles an Apache-style format string (e.g.
+//! `%h %l %u %t "%r" %>s %b "%{Referer}i" "%{User-Agent}i"`) into an
+//! ordered [`FieldSpec`] list once, then [`find_fields_with_spec`] walks a
+//! line against it generically — the same zero-copy byte-slice approach,
+//! just driven by data instead of hardcoded arithmetic.
+
+use memchr::memchr;
+use std::collections::HashMap;
+
+/// How a field's bytes are delimited in the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Runs until the next space (or end of line) — the default for most
+    /// directives, e.g. `%h`, `%>s`, `%b`.
+    Whitespace,
+    /// Enclosed in `[` `]` (used by `%t`).
+    Bracket,
+    /// Enclosed in `"` `"`; embedded spaces are part of the value rather
+    /// than a split point (used by `%r` and header captures).
+    Quote,
+}
+
+/// One token of a parsed format string: a field name plus how its value is
+/// delimited in the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSpec {
+    pub name: String,
+    pub delimiter: Delimiter,
+}
+
+/// Byte ranges recorded for each named field found while walking a line
+/// against a `&[FieldSpec]`. A field absent from a truncated/malformed
+/// line is simply missing from the map rather than an error.
+#[derive(Debug, Clone, Default)]
+pub struct FieldMap {
+    ranges: HashMap<String, (usize, usize)>,
+}
+
+impl FieldMap {
+    /// The raw bytes of `name` within the `line` this map was built from,
+    /// or `None` if the field wasn't present.
+    pub fn get<'a>(&self, line: &'a [u8], name: &str) -> Option<&'a [u8]> {
+        self.ranges.get(name).map(|&(start, end)| &line[start..end])
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.ranges.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+/// Read one `%`-directive starting at `chars[start]` (which must be `%`),
+/// returning its canonical field name and how many characters it consumed
+/// (including the leading `%`).
+fn read_directive(chars: &[char], start: usize) -> Option<(String, usize)> {
+    if chars.get(start) != Some(&'%') {
+        return None;
+    }
+    let mut i = start + 1;
+
+    // Optional modifier, e.g. the `>` in `%>s`.
+    if chars.get(i) == Some(&'>') {
+        i += 1;
+    }
+
+    // Header capture: %{Name}i (request header) or %{Name}o (response header).
+    if chars.get(i) == Some(&'{') {
+        let brace_start = i + 1;
+        let mut j = brace_start;
+        while chars.get(j).is_some_and(|c| *c != '}') {
+            j += 1;
+        }
+        let header_name: String = chars[brace_start..j].iter().collect();
+        let kind = chars.get(j + 1).copied().unwrap_or('i');
+        let name = format!("header_{}_{}", kind, header_name.to_lowercase().replace('-', "_"));
+        return Some((name, j + 2 - start));
+    }
+
+    let directive = chars.get(i).copied()?;
+    let name = match directive {
+        'h' => "remote_host".to_string(),
+        'l' => "remote_logname".to_string(),
+        'u' => "remote_user".to_string(),
+        't' => "time".to_string(),
+        'r' => "request".to_string(),
+        's' => "status".to_string(),
+        'b' => "size".to_string(),
+        other => format!("field_{}", other),
+    };
+
+    Some((name, i + 1 - start))
+}
+
+/// Parse an Apache-style `LogFormat` directive into an ordered list of
+/// field specs, e.g. `%h %l %u %t "%r" %>s %b` -> 7 specs. Literal text
+/// between directives (spaces, quotes, brackets) establishes each field's
+/// delimiter rather than being stored itself.
+pub fn parse_format_string(format: &str) -> Vec<FieldSpec> {
+    let chars: Vec<char> = format.chars().collect();
+    let mut specs = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == '"' {
+            // Quote-delimited field: "%directive"
+            i += 1;
+            if let Some((name, consumed)) = read_directive(&chars, i) {
+                specs.push(FieldSpec { name, delimiter: Delimiter::Quote });
+                i += consumed;
+            }
+            // Skip to the closing quote of the format string's own literal.
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            if i < chars.len() {
+                i += 1;
+            }
+            continue;
+        }
+
+        if chars[i] == '%' {
+            if let Some((name, consumed)) = read_directive(&chars, i) {
+                let delimiter = if name == "time" { Delimiter::Bracket } else { Delimiter::Whitespace };
+                specs.push(FieldSpec { name, delimiter });
+                i += consumed;
+                continue;
+            }
+        }
+
+        i += 1;
+    }
+
+    specs
+}
+
+/// The field specs for Apache/Nginx's default combined log format:
+/// `%h %l %u %t "%r" %>s %b "%{Referer}i" "%{User-Agent}i"`.
+pub fn combined_format_specs() -> Vec<FieldSpec> {
+    parse_format_string(r#"%h %l %u %t "%r" %>s %b "%{Referer}i" "%{User-Agent}i""#)
+}
+
+/// Walk `line` against `specs` left-to-right, recording the byte range of
+/// each field. Whitespace-delimited fields run until the next space (or
+/// end of line, for a trailing field); bracket/quote-delimited fields run
+/// until their matching close, and embedded spaces inside a quoted field
+/// are part of the value rather than a split point. A bare `-` in a
+/// whitespace-delimited field (the common "empty value" placeholder) is
+/// still recorded as a normal field; callers that care can compare its
+/// bytes against `-` themselves. Returns `None` only if no field could be
+/// matched at all (e.g. an empty line).
+pub fn find_fields_with_spec(line: &[u8], specs: &[FieldSpec]) -> Option<FieldMap> {
+    let mut map = FieldMap::default();
+    let mut pos = 0;
+
+    for spec in specs {
+        // Skip separator characters left over between fields (the space
+        // before `[`, or the `"`/`"` quotes themselves).
+        while pos < line.len() && matches!(line[pos], b' ' | b'[' | b']' | b'"') {
+            pos += 1;
+        }
+        if pos >= line.len() {
+            break;
+        }
+
+        let (start, end, next_pos) = match spec.delimiter {
+            Delimiter::Whitespace => {
+                let start = pos;
+                let end = memchr(b' ', &line[pos..]).map(|i| pos + i).unwrap_or(line.len());
+                (start, end, end)
+            }
+            Delimiter::Bracket => {
+                let start = pos;
+                let end = memchr(b']', &line[pos..]).map(|i| pos + i).unwrap_or(line.len());
+                (start, end, end + 1)
+            }
+            Delimiter::Quote => {
+                let start = pos;
+                let end = memchr(b'"', &line[pos..]).map(|i| pos + i).unwrap_or(line.len());
+                (start, end, end + 1)
+            }
+        };
+
+        map.ranges.insert(spec.name.clone(), (start, end));
+        pos = next_pos;
+    }
+
+    if map.is_empty() {
+        None
+    } else {
+        Some(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format_string_combined_log() {
+        let specs = combined_format_specs();
+        let names: Vec<&str> = specs.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["remote_host", "remote_logname", "remote_user", "time", "request", "status", "size", "header_i_referer", "header_i_user_agent"]
+        );
+        assert_eq!(specs[3].delimiter, Delimiter::Bracket);
+        assert_eq!(specs[4].delimiter, Delimiter::Quote);
+        assert_eq!(specs[5].delimiter, Delimiter::Whitespace);
+    }
+
+    #[test]
+    fn test_find_fields_with_spec_combined_log() {
+        let specs = combined_format_specs();
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET /index.html HTTP/1.1\" 200 2326 \"-\" \"Mozilla/5.0\"";
+        let map = find_fields_with_spec(line, &specs).unwrap();
+
+        assert_eq!(map.get(line, "remote_host"), Some(&b"192.168.1.1"[..]));
+        assert_eq!(map.get(line, "time"), Some(&b"10/Oct/2024:13:55:36 +0000"[..]));
+        assert_eq!(map.get(line, "request"), Some(&b"GET /index.html HTTP/1.1"[..]));
+        assert_eq!(map.get(line, "status"), Some(&b"200"[..]));
+        assert_eq!(map.get(line, "size"), Some(&b"2326"[..]));
+    }
+
+    #[test]
+    fn test_find_fields_with_spec_handles_dash_placeholder() {
+        let specs = combined_format_specs();
+        let line = b"192.168.1.1 - - [10/Oct/2024:13:55:36 +0000] \"GET / HTTP/1.1\" 304 -";
+        let map = find_fields_with_spec(line, &specs).unwrap();
+        assert_eq!(map.get(line, "size"), Some(&b"-"[..]));
+    }
+
+    #[test]
+    fn test_find_fields_with_spec_custom_format_without_referer_ua() {
+        // A shorter custom LogFormat that drops the referer/user-agent fields.
+        let specs = parse_format_string(r#"%h %t "%r" %>s %b"#);
+        let line = b"10.0.0.1 [11/Oct/2024:09:00:00 +0000] \"POST /api/orders HTTP/1.1\" 201 55";
+        let map = find_fields_with_spec(line, &specs).unwrap();
+
+        assert_eq!(map.get(line, "remote_host"), Some(&b"10.0.0.1"[..]));
+        assert_eq!(map.get(line, "request"), Some(&b"POST /api/orders HTTP/1.1"[..]));
+        assert_eq!(map.get(line, "status"), Some(&b"201"[..]));
+        assert_eq!(map.get(line, "size"), Some(&b"55"[..]));
+        assert!(!map.contains("header_i_referer"));
+    }
+}
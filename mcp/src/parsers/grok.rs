@@ -0,0 +1,161 @@
+use memmap2::Mmap;
+use polars::prelude::*;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use super::ParseError;
+
+/// Grok parser for user-defined log formats.
+///
+/// A grok template like `%{TIMESTAMP:ts} %{IP:client} %{WORD:method} %{NUMBER:status}`
+/// is compiled into a single regex whose named capture groups become
+/// DataFrame columns, so ad-hoc/vendor formats can be queried the same way
+/// as the built-in parsers without writing a dedicated parser for each one.
+
+/// Named sub-patterns available inside `%{NAME:field}` tokens, expanding to
+/// the regex fragment they represent.
+fn builtin_patterns() -> HashMap<&'static str, &'static str> {
+    let mut m = HashMap::new();
+    m.insert("IP", r"(?:[0-9]{1,3}\.){3}[0-9]{1,3}");
+    m.insert("NUMBER", r"-?\d+(?:\.\d+)?");
+    m.insert("WORD", r"\w+");
+    m.insert("TIMESTAMP", r"[0-9]{4}-[0-9]{2}-[0-9]{2}[T ][0-9]{2}:[0-9]{2}:[0-9]{2}(?:\.\d+)?(?:Z|[+-][0-9]{2}:?[0-9]{2})?");
+    m.insert("GREEDYDATA", r".*");
+    m
+}
+
+/// A small library of saved grok pipelines for common custom formats, keyed
+/// by name so callers don't have to respecify the template every call.
+pub fn named_pipeline(name: &str) -> Option<&'static str> {
+    match name {
+        "nginx_custom" => Some(
+            r#"%{IP:client} - - [%{GREEDYDATA:timestamp}] "%{WORD:method} %{GREEDYDATA:path} HTTP/%{NUMBER:http_version}" %{NUMBER:status} %{NUMBER:size}"#
+        ),
+        "key_value" => Some(r"%{TIMESTAMP:timestamp} %{WORD:level} %{GREEDYDATA:message}"),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    Integer,
+    Text,
+}
+
+/// A grok template compiled into a regex plus the inferred dtype of each
+/// named field (integer for `%{NUMBER:...}`, string otherwise).
+struct GrokPattern {
+    regex: Regex,
+    fields: Vec<(String, FieldType)>,
+}
+
+/// Compile a grok template, expanding each `%{TYPE:field}` token into a named
+/// capture group and escaping the literal text between tokens.
+fn compile(template: &str) -> Result<GrokPattern, ParseError> {
+    let builtins = builtin_patterns();
+    let token_re = Regex::new(r"%\{(\w+):(\w+)\}").unwrap();
+
+    let mut fields = Vec::new();
+    let mut pattern = String::new();
+    let mut last_end = 0;
+
+    for caps in token_re.captures_iter(template) {
+        let whole = caps.get(0).unwrap();
+        pattern.push_str(&regex::escape(&template[last_end..whole.start()]));
+
+        let type_name = caps.get(1).unwrap().as_str();
+        let field_name = caps.get(2).unwrap().as_str();
+
+        let fragment = builtins.get(type_name).ok_or_else(|| {
+            ParseError::ParseFailed(format!("Unknown grok pattern type: {}", type_name))
+        })?;
+
+        pattern.push_str(&format!("(?P<{}>{})", field_name, fragment));
+
+        let field_type = if type_name == "NUMBER" { FieldType::Integer } else { FieldType::Text };
+        fields.push((field_name.to_string(), field_type));
+
+        last_end = whole.end();
+    }
+    pattern.push_str(&regex::escape(&template[last_end..]));
+
+    let regex = Regex::new(&format!("^{}$", pattern))
+        .map_err(|e| ParseError::ParseFailed(format!("Invalid grok template: {}", e)))?;
+
+    Ok(GrokPattern { regex, fields })
+}
+
+/// Parse a log file against a grok template into a LazyFrame, one column per
+/// named field plus a `raw` column holding the unparsed line (lines that
+/// don't match the template get all-null field columns).
+pub fn parse(path: &Path, template: &str) -> Result<LazyFrame, ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let content = std::str::from_utf8(&mmap)
+        .map_err(|e| ParseError::ParseFailed(format!("Invalid UTF-8: {}", e)))?;
+
+    let grok = compile(template)?;
+
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    let mut field_values: Vec<Vec<Option<String>>> =
+        vec![Vec::with_capacity(lines.len()); grok.fields.len()];
+    let mut raw_lines = Vec::with_capacity(lines.len());
+
+    for line in &lines {
+        raw_lines.push(line.to_string());
+
+        if let Some(caps) = grok.regex.captures(line) {
+            for (i, (name, _)) in grok.fields.iter().enumerate() {
+                field_values[i].push(caps.name(name).map(|m| m.as_str().to_string()));
+            }
+        } else {
+            for values in field_values.iter_mut() {
+                values.push(None);
+            }
+        }
+    }
+
+    let mut columns: Vec<Column> = Vec::with_capacity(grok.fields.len() + 1);
+    for (i, (name, field_type)) in grok.fields.iter().enumerate() {
+        match field_type {
+            FieldType::Integer => {
+                let parsed: Vec<Option<i64>> = field_values[i]
+                    .iter()
+                    .map(|v| v.as_ref().and_then(|s| s.parse::<i64>().ok()))
+                    .collect();
+                columns.push(Column::new(name.into(), parsed));
+            }
+            FieldType::Text => {
+                columns.push(Column::new(name.into(), field_values[i].clone()));
+            }
+        }
+    }
+    columns.push(Column::new("raw".into(), raw_lines));
+
+    let df = DataFrame::new(columns)?;
+    Ok(df.lazy())
+}
+
+/// Parse multiple files against a grok template and concatenate into a
+/// single LazyFrame, mirroring [`super::parse_multiple`].
+pub fn parse_multiple(pattern: &str, template: &str, exclude: &[String]) -> Result<LazyFrame, ParseError> {
+    let paths = super::resolve_paths(pattern, exclude)?;
+
+    let mut frames: Vec<LazyFrame> = Vec::new();
+    for path in &paths {
+        let mut lf = parse(path, template)?;
+        let file_name = path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        lf = lf.with_column(lit(file_name).alias("_source_file"));
+        frames.push(lf);
+    }
+
+    if frames.is_empty() {
+        return Err(ParseError::ParseFailed("No valid log files found".to_string()));
+    }
+
+    concat(&frames, UnionArgs::default()).map_err(ParseError::from)
+}
@@ -1,7 +1,12 @@
 pub mod apache;
 pub mod apache_simd;
 pub mod csv;
+pub mod decompress;
+pub mod detect;
+pub mod format_spec;
+pub mod grok;
 pub mod json;
+pub mod source;
 pub mod syslog;
 pub mod syslog_simd;
 
@@ -19,6 +24,8 @@ pub enum ParseError {
     PolarsError(#[from] PolarsError),
     #[error("Unknown format")]
     UnknownFormat,
+    #[error("Failed to fetch remote source {0}: {1}")]
+    FetchFailed(String, String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,6 +35,8 @@ pub enum LogFormat {
     Syslog,
     Json,
     Csv,
+    /// User-defined format described by a grok template; see [`grok`].
+    Grok,
     Auto,
 }
 
@@ -39,15 +48,44 @@ impl LogFormat {
             "syslog" => LogFormat::Syslog,
             "json" | "jsonl" | "ndjson" => LogFormat::Json,
             "csv" | "tsv" => LogFormat::Csv,
+            "grok" => LogFormat::Grok,
             _ => LogFormat::Auto,
         }
     }
+
+    /// Like [`from_str`](LogFormat::from_str), but errors on an unrecognized
+    /// name instead of silently falling back to [`LogFormat::Auto`]. Use
+    /// this where an explicit, mistyped `format` argument should surface to
+    /// the caller rather than be masked by content-sniffing.
+    pub fn try_from_str(s: &str) -> Result<Self, ParseError> {
+        match s.to_lowercase().as_str() {
+            "apache" => Ok(LogFormat::Apache),
+            "nginx" => Ok(LogFormat::Nginx),
+            "syslog" => Ok(LogFormat::Syslog),
+            "json" | "jsonl" | "ndjson" => Ok(LogFormat::Json),
+            "csv" | "tsv" => Ok(LogFormat::Csv),
+            "grok" => Ok(LogFormat::Grok),
+            "auto" => Ok(LogFormat::Auto),
+            _ => Err(ParseError::UnknownFormat),
+        }
+    }
 }
 
-/// Detect log format by examining file extension and content
+/// Detect log format by examining file extension and content. `path` is
+/// used both for the extension check and for content sniffing; use
+/// [`detect_format_at`] when the bytes to sniff live somewhere other than
+/// `path` itself (e.g. a decompressed temp file).
 pub fn detect_format(path: &Path) -> LogFormat {
-    // First check extension
-    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+    detect_format_at(path, path)
+}
+
+/// Detect log format using `content_path` for the sampled content scoring
+/// and `name_hint` for the extension check (stripping a trailing
+/// compression extension first, so `access.ndjson.gz` is still recognized
+/// as JSON via its inner `.ndjson` extension).
+pub fn detect_format_at(content_path: &Path, name_hint: &Path) -> LogFormat {
+    let inner_name = decompress::strip_compression_ext(name_hint);
+    if let Some(ext) = inner_name.extension().and_then(|e| e.to_str()) {
         match ext.to_lowercase().as_str() {
             "json" | "jsonl" | "ndjson" => return LogFormat::Json,
             "csv" => return LogFormat::Csv,
@@ -56,60 +94,180 @@ pub fn detect_format(path: &Path) -> LogFormat {
         }
     }
 
-    // Try to detect from content
-    if let Ok(content) = std::fs::read_to_string(path).map(|s| s.lines().next().unwrap_or("").to_string()) {
-        if content.starts_with('{') {
-            return LogFormat::Json;
-        }
-        if content.contains(',') && !content.contains(" - - [") {
-            return LogFormat::Csv;
-        }
-        if content.contains(" - - [") || content.contains("\" ") {
-            return LogFormat::Apache;
-        }
-        if content.starts_with('<') || content.contains("]: ") {
-            return LogFormat::Syslog;
-        }
-    }
+    detect::detect_format_sampled(content_path, detect::DEFAULT_SAMPLE_SIZE)
+        .map(|r| r.format)
+        .unwrap_or(LogFormat::Auto)
+}
 
-    LogFormat::Auto
+/// Like [`detect_format_at`], but returns the full per-format confidence
+/// breakdown instead of just the winning format, so callers (e.g. the MCP
+/// tool layer) can surface detection confidence to the user.
+pub fn detect_format_with_confidence(path: &Path) -> Result<detect::DetectionResult, ParseError> {
+    detect::detect_format_sampled(path, detect::DEFAULT_SAMPLE_SIZE)
 }
 
-/// Parse logs from a file into a LazyFrame
+/// Parse logs from a file into a LazyFrame. Transparently decompresses
+/// gzip/zstd/bzip2/xz archives first, sniffing format from the decompressed
+/// content (and the original name, for extension-based detection) rather
+/// than the compressed bytes.
 pub fn parse_logs(path: &Path, format: LogFormat) -> Result<LazyFrame, ParseError> {
+    let source = decompress::open(path)?;
+    let scan_path = source.path();
+
     let format = if format == LogFormat::Auto {
-        detect_format(path)
+        detect_format_at(scan_path, path)
     } else {
         format
     };
 
-    match format {
-        LogFormat::Apache | LogFormat::Nginx => apache::parse(path),
-        LogFormat::Syslog => syslog::parse(path),
-        LogFormat::Json => json::parse(path),
-        LogFormat::Csv => csv::parse(path),
+    let lf = match format {
+        LogFormat::Apache | LogFormat::Nginx => apache::parse(scan_path),
+        LogFormat::Syslog => syslog::parse(scan_path),
+        LogFormat::Json => json::parse(scan_path),
+        LogFormat::Csv => csv::parse(scan_path),
+        LogFormat::Grok => Err(ParseError::ParseFailed(
+            "Grok format requires a template; use grok::parse/parse_multiple directly".to_string()
+        )),
         LogFormat::Auto => {
             // Try each parser in order
-            json::parse(path)
-                .or_else(|_| csv::parse(path))
-                .or_else(|_| apache::parse(path))
-                .or_else(|_| syslog::parse(path))
+            json::parse(scan_path)
+                .or_else(|_| csv::parse(scan_path))
+                .or_else(|_| apache::parse(scan_path))
+                .or_else(|_| syslog::parse(scan_path))
         }
+    }?;
+
+    // `csv`/`json` build a genuinely lazy scan over `scan_path`; if that's a
+    // decompressed temp file, it's about to be removed when `source` drops,
+    // so force collection now rather than leave a dangling lazy read.
+    if source.is_temp() {
+        Ok(lf.collect()?.lazy())
+    } else {
+        Ok(lf)
     }
 }
 
 /// Expand glob pattern and return matching file paths
 pub fn expand_glob(pattern: &str) -> Result<Vec<std::path::PathBuf>, ParseError> {
-    let paths: Vec<_> = glob::glob(pattern)
-        .map_err(|e| ParseError::ParseFailed(e.to_string()))?
-        .filter_map(Result::ok)
+    resolve_paths(pattern, &[])
+}
+
+/// Split a glob pattern into a literal base directory and the remaining
+/// pattern relative to it, e.g. `/var/log/**/*.log` -> (`/var/log`, `**/*.log`).
+/// This lets traversal start at the deepest directory that's guaranteed to
+/// exist instead of globbing from somewhere irrelevant.
+fn split_glob_base(pattern: &str) -> (std::path::PathBuf, String) {
+    let is_wild = |s: &str| s.contains('*') || s.contains('?') || s.contains('[');
+    let parts: Vec<&str> = pattern.split('/').collect();
+
+    let mut base_parts: Vec<&str> = Vec::new();
+    let mut rest_start = parts.len();
+    for (i, part) in parts.iter().enumerate() {
+        if is_wild(part) {
+            rest_start = i;
+            break;
+        }
+        base_parts.push(part);
+    }
+
+    let base = if base_parts.is_empty() {
+        std::path::PathBuf::from(".")
+    } else {
+        std::path::PathBuf::from(base_parts.join("/"))
+    };
+
+    let rest = if rest_start >= parts.len() {
+        String::new()
+    } else {
+        parts[rest_start..].join("/")
+    };
+
+    (base, rest)
+}
+
+/// Walk `base` lazily, matching each file against `pattern` (relative to
+/// `base`) while skipping any subtree the pattern can't match in and any
+/// entry matched by `excludes` — so e.g. `/var/log/**/*.log` with
+/// `exclude: ["*.gz", "*.1"]` never globs rotated/compressed files at all.
+fn walk_matching(
+    base: &Path,
+    pattern: &str,
+    excludes: &[glob::Pattern],
+) -> Vec<std::path::PathBuf> {
+    let pattern_str = if pattern.is_empty() { "*" } else { pattern };
+    let Ok(compiled) = glob::Pattern::new(pattern_str) else {
+        return Vec::new();
+    };
+
+    let recursive = pattern_str.contains("**");
+    let max_depth = pattern_str.split('/').count();
+    let mut results = Vec::new();
+
+    fn walk_dir(
+        dir: &Path,
+        rel: &Path,
+        pattern: &glob::Pattern,
+        excludes: &[glob::Pattern],
+        recursive: bool,
+        max_depth: usize,
+        depth: usize,
+        results: &mut Vec<std::path::PathBuf>,
+    ) {
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let rel_path = rel.join(entry.file_name());
+            let rel_str = rel_path.to_string_lossy().to_string();
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if excludes.iter().any(|ex| ex.matches(&name) || ex.matches(&rel_str)) {
+                continue;
+            }
+
+            if path.is_dir() {
+                // A non-recursive pattern can't match anything deeper than
+                // its own component count, so don't even descend.
+                if recursive || depth + 1 < max_depth {
+                    walk_dir(&path, &rel_path, pattern, excludes, recursive, max_depth, depth + 1, results);
+                }
+            } else if pattern.matches(&rel_str) {
+                results.push(path);
+            }
+        }
+    }
+
+    if base.is_dir() {
+        walk_dir(base, Path::new(""), &compiled, excludes, recursive, max_depth, 0, &mut results);
+    }
+
+    results
+}
+
+/// Resolve a path/glob pattern to matching files, honoring `exclude` glob
+/// patterns matched against each candidate during traversal (so excluded
+/// subtrees are skipped rather than globbed and filtered afterward).
+pub fn resolve_paths(pattern: &str, exclude: &[String]) -> Result<Vec<std::path::PathBuf>, ParseError> {
+    let single = Path::new(pattern);
+    if single.is_file() {
+        return Ok(vec![single.to_path_buf()]);
+    }
+
+    let excludes: Vec<glob::Pattern> = exclude
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
         .collect();
 
+    let (base, rest) = split_glob_base(pattern);
+    let paths = if base.is_dir() && !rest.is_empty() {
+        walk_matching(&base, &rest, &excludes)
+    } else {
+        Vec::new()
+    };
+
     if paths.is_empty() {
-        // Maybe it's a single file path
-        let path = Path::new(pattern);
-        if path.exists() {
-            return Ok(vec![path.to_path_buf()]);
+        if single.exists() {
+            return Ok(vec![single.to_path_buf()]);
         }
         return Err(ParseError::ParseFailed(format!("No files found matching: {}", pattern)));
     }
@@ -117,20 +275,61 @@ pub fn expand_glob(pattern: &str) -> Result<Vec<std::path::PathBuf>, ParseError>
     Ok(paths)
 }
 
-/// Parse multiple files and concatenate into a single LazyFrame
-pub fn parse_multiple(pattern: &str, format: LogFormat) -> Result<LazyFrame, ParseError> {
-    let paths = expand_glob(pattern)?;
+/// A deduplicated set of files ready for the mmap-based fast-path scanners
+/// in [`apache_simd`], plus the guards that keep any
+/// decompressed temp files alive for as long as this value lives. Drop this
+/// only after the scan is done.
+pub struct ResolvedScanPaths {
+    paths: Vec<std::path::PathBuf>,
+    _sources: Vec<decompress::DecompressedSource>,
+}
+
+impl ResolvedScanPaths {
+    pub fn paths(&self) -> &[std::path::PathBuf] {
+        &self.paths
+    }
+}
+
+/// Like [`resolve_paths`], but for callers that hand the result straight to
+/// a `mmap`-based fast-path scanner rather than a `LazyFrame` pipeline:
+/// expands `pattern` to a deduplicated file list (so a glob that matches the
+/// same file twice, e.g. via an overlapping `**`, only scans it once) and
+/// transparently decompresses any `.gz`/`.zst`/`.bz2`/`.xz` member first, so
+/// rotated/archived logs are included in a whole-directory sweep without
+/// the caller shelling out for globbing or decompression themselves.
+pub fn resolve_paths_for_scan(pattern: &str, exclude: &[String]) -> Result<ResolvedScanPaths, ParseError> {
+    let candidates = resolve_paths(pattern, exclude)?;
+
+    let mut seen = std::collections::HashSet::new();
+    let mut paths = Vec::new();
+    let mut sources = Vec::new();
+
+    for candidate in candidates {
+        let key = candidate.canonicalize().unwrap_or_else(|_| candidate.clone());
+        if !seen.insert(key) {
+            continue;
+        }
+        let source = decompress::open(&candidate)?;
+        paths.push(source.path().to_path_buf());
+        sources.push(source);
+    }
+
+    Ok(ResolvedScanPaths { paths, _sources: sources })
+}
+
+/// Parse multiple files and concatenate into a single LazyFrame. `pattern`
+/// may be a local path/glob, or an `http(s)://`/`s3://` URL (see
+/// [`source::LogSource`]) — remote objects are downloaded to temp files
+/// first, with `_source_file` set to the full URL rather than a bare name.
+pub fn parse_multiple(pattern: &str, format: LogFormat, exclude: &[String]) -> Result<LazyFrame, ParseError> {
+    let src = source::LogSource::parse(pattern);
+    let entries = source::resolve(&src, exclude)?;
 
     let mut frames: Vec<LazyFrame> = Vec::new();
 
-    for path in &paths {
+    for (display_name, path) in &entries {
         let mut lf = parse_logs(path, format)?;
-        // Add source file column
-        let file_name = path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-        lf = lf.with_column(lit(file_name).alias("_source_file"));
+        lf = lf.with_column(lit(display_name.clone()).alias("_source_file"));
         frames.push(lf);
     }
 
@@ -1,6 +1,7 @@
 use polars::prelude::*;
 use rayon::prelude::*;
 use regex::Regex;
+use serde::Serialize;
 use std::fs::File;
 use std::path::Path;
 use memmap2::Mmap;
@@ -11,6 +12,9 @@ use super::ParseError;
 ///
 /// Supports RFC 3164 format (most common):
 /// Example: Dec 10 10:45:23 myhost sshd[12345]: Accepted publickey for user
+///
+/// Also supports RFC 5424 (structured syslog), auto-detected per line:
+/// Example: <34>1 2024-12-10T10:45:23.003Z myhost su 12345 ID47 [exampleSDID@32473 iut="3"] message
 
 // Parsed syslog entry
 struct SyslogEntry {
@@ -22,6 +26,107 @@ struct SyslogEntry {
     message: String,
     level: String,
     raw: String,
+    // RFC 5424 only
+    version: Option<i32>,
+    proc_id: Option<String>,
+    msg_id: Option<String>,
+    structured_data: Option<String>,
+}
+
+/// A single RFC 5424 STRUCTURED-DATA element: `[SD-ID key="value" ...]`.
+#[derive(Debug, Serialize)]
+struct StructuredDataElement {
+    id: String,
+    params: Vec<(String, String)>,
+}
+
+/// Parse the STRUCTURED-DATA + MSG tail of an RFC 5424 line.
+///
+/// Handles multiple back-to-back `[SD-ID ...]` elements and values with
+/// escaped quotes/backslashes/brackets (`\"`, `\\`, `\]`) per RFC 5424 §6.3.
+fn parse_structured_data(s: &str) -> (Vec<StructuredDataElement>, &str) {
+    let s = s.trim_start();
+    if s.starts_with('-') {
+        return (Vec::new(), s[1..].trim_start());
+    }
+
+    let bytes = s.as_bytes();
+    let mut elements = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() && bytes[i] == b'[' {
+        let start = i;
+        i += 1;
+        let mut in_quotes = false;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' if in_quotes && i + 1 < bytes.len() => i += 1,
+                b'"' => in_quotes = !in_quotes,
+                b']' if !in_quotes => {
+                    i += 1;
+                    break;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let inner_end = if i > start + 1 && bytes[i - 1] == b']' { i - 1 } else { i };
+        elements.push(parse_sd_element(&s[start + 1..inner_end]));
+    }
+
+    (elements, s[i..].trim_start())
+}
+
+/// Parse one `SD-ID key1="val1" key2="val2"` element body (without the
+/// surrounding brackets).
+fn parse_sd_element(s: &str) -> StructuredDataElement {
+    let mut parts = s.splitn(2, ' ');
+    let id = parts.next().unwrap_or("").to_string();
+    let rest = parts.next().unwrap_or("");
+
+    let mut params = Vec::new();
+    let bytes = rest.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        while i < bytes.len() && bytes[i] == b' ' {
+            i += 1;
+        }
+        if i >= bytes.len() {
+            break;
+        }
+
+        let key_start = i;
+        while i < bytes.len() && bytes[i] != b'=' {
+            i += 1;
+        }
+        let key = rest[key_start..i].to_string();
+        i += 1; // skip '='
+
+        if i < bytes.len() && bytes[i] == b'"' {
+            i += 1;
+            let mut value = String::new();
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' if i + 1 < bytes.len() => {
+                        value.push(bytes[i + 1] as char);
+                        i += 2;
+                        continue;
+                    }
+                    b'"' => {
+                        i += 1;
+                        break;
+                    }
+                    c => value.push(c as char),
+                }
+                i += 1;
+            }
+            params.push((key, value));
+        }
+    }
+
+    StructuredDataElement { id, params }
 }
 
 /// Convert syslog priority to human-readable level
@@ -57,17 +162,53 @@ pub fn parse(path: &Path) -> Result<LazyFrame, ParseError> {
         r"^(?:<(\d+)>)?(\w{3}\s+\d+\s+\d+:\d+:\d+)\s+(\S+)\s+(\S+?)(?:\[(\d+)\])?:\s*(.*)$"
     ).unwrap();
 
+    // RFC 5424: <PRI>1 TIMESTAMP HOST APP PROCID MSGID SD MSG
+    // The trailing SD+MSG is captured whole and parsed separately, since
+    // STRUCTURED-DATA can hold multiple bracketed elements with escaped
+    // quotes that a single regex can't cleanly express.
+    let re_5424 = Regex::new(
+        r"^<(\d+)>1\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(.*)$"
+    ).unwrap();
+
     // Collect lines
     let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
     let line_count = lines.len();
 
-    // Parse in parallel
+    // Parse in parallel, auto-selecting RFC 5424 vs RFC 3164 per line
     let entries: Vec<SyslogEntry> = lines
         .par_iter()
         .map(|line| {
             let line = line.trim();
 
-            if let Some(caps) = re_3164.captures(line) {
+            if let Some(caps) = re_5424.captures(line) {
+                let pri: Option<i32> = caps.get(1).and_then(|m| m.as_str().parse().ok());
+                let tail = caps.get(7).map(|m| m.as_str()).unwrap_or("");
+                let (sd_elements, message) = parse_structured_data(tail);
+                let structured_data = if sd_elements.is_empty() {
+                    None
+                } else {
+                    serde_json::to_string(&sd_elements).ok()
+                };
+
+                let nilable = |m: Option<regex::Match>| -> Option<String> {
+                    m.map(|m| m.as_str()).filter(|s| *s != "-").map(|s| s.to_string())
+                };
+
+                SyslogEntry {
+                    priority: pri,
+                    timestamp: caps.get(2).map(|m| m.as_str()).unwrap_or("").to_string(),
+                    hostname: caps.get(3).map(|m| m.as_str()).unwrap_or("-").to_string(),
+                    process: caps.get(4).map(|m| m.as_str()).unwrap_or("-").to_string(),
+                    pid: None,
+                    message: message.to_string(),
+                    level: priority_to_level(pri),
+                    raw: line.to_string(),
+                    version: Some(1),
+                    proc_id: nilable(caps.get(5)),
+                    msg_id: nilable(caps.get(6)),
+                    structured_data,
+                }
+            } else if let Some(caps) = re_3164.captures(line) {
                 let pri: Option<i32> = caps.get(1).and_then(|m| m.as_str().parse().ok());
 
                 SyslogEntry {
@@ -79,6 +220,10 @@ pub fn parse(path: &Path) -> Result<LazyFrame, ParseError> {
                     message: caps.get(6).map(|m| m.as_str()).unwrap_or("").to_string(),
                     level: priority_to_level(pri),
                     raw: line.to_string(),
+                    version: None,
+                    proc_id: None,
+                    msg_id: None,
+                    structured_data: None,
                 }
             } else {
                 // Unstructured log line
@@ -91,6 +236,10 @@ pub fn parse(path: &Path) -> Result<LazyFrame, ParseError> {
                     message: line.to_string(),
                     level: "UNKNOWN".to_string(),
                     raw: line.to_string(),
+                    version: None,
+                    proc_id: None,
+                    msg_id: None,
+                    structured_data: None,
                 }
             }
         })
@@ -105,6 +254,10 @@ pub fn parse(path: &Path) -> Result<LazyFrame, ParseError> {
     let mut messages = Vec::with_capacity(line_count);
     let mut levels = Vec::with_capacity(line_count);
     let mut raw_lines = Vec::with_capacity(line_count);
+    let mut versions: Vec<Option<i32>> = Vec::with_capacity(line_count);
+    let mut proc_ids: Vec<Option<String>> = Vec::with_capacity(line_count);
+    let mut msg_ids: Vec<Option<String>> = Vec::with_capacity(line_count);
+    let mut structured_datas: Vec<Option<String>> = Vec::with_capacity(line_count);
 
     for entry in entries {
         priorities.push(entry.priority);
@@ -115,6 +268,10 @@ pub fn parse(path: &Path) -> Result<LazyFrame, ParseError> {
         messages.push(entry.message);
         levels.push(entry.level);
         raw_lines.push(entry.raw);
+        versions.push(entry.version);
+        proc_ids.push(entry.proc_id);
+        msg_ids.push(entry.msg_id);
+        structured_datas.push(entry.structured_data);
     }
 
     let df = DataFrame::new(vec![
@@ -126,7 +283,75 @@ pub fn parse(path: &Path) -> Result<LazyFrame, ParseError> {
         Column::new("message".into(), messages),
         Column::new("level".into(), levels),
         Column::new("raw".into(), raw_lines),
+        Column::new("version".into(), versions),
+        Column::new("proc_id".into(), proc_ids),
+        Column::new("msg_id".into(), msg_ids),
+        Column::new("structured_data".into(), structured_datas),
     ])?;
 
     Ok(df.lazy())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_structured_data_none() {
+        let (elements, msg) = parse_structured_data("- login failed");
+        assert!(elements.is_empty());
+        assert_eq!(msg, "login failed");
+    }
+
+    #[test]
+    fn test_parse_structured_data_single_element() {
+        let (elements, msg) = parse_structured_data(r#"[exampleSDID@32473 iut="3" eventSource="App"] login failed"#);
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].id, "exampleSDID@32473");
+        assert_eq!(elements[0].params, vec![
+            ("iut".to_string(), "3".to_string()),
+            ("eventSource".to_string(), "App".to_string()),
+        ]);
+        assert_eq!(msg, "login failed");
+    }
+
+    #[test]
+    fn test_parse_structured_data_multiple_elements_and_escapes() {
+        let (elements, msg) = parse_structured_data(r#"[a@1 k="v\"v"][b@2 k2="y"] done"#);
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].id, "a@1");
+        assert_eq!(elements[0].params, vec![("k".to_string(), "v\"v".to_string())]);
+        assert_eq!(elements[1].id, "b@2");
+        assert_eq!(elements[1].params, vec![("k2".to_string(), "y".to_string())]);
+        assert_eq!(msg, "done");
+    }
+
+    fn write_temp_syslog(data: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "syslog_test_{}_{}.log",
+            std::process::id(),
+            data.len()
+        ));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_mixed_3164_and_5424() {
+        let data = b"Dec 10 10:45:23 myhost sshd[12345]: Accepted publickey for user\n\
+<34>1 2024-12-10T10:45:23.003Z myhost su 12345 ID47 [exampleSDID@32473 iut=\"3\"] password changed\n";
+        let tmp_path = write_temp_syslog(data);
+        let df = parse(&tmp_path).unwrap().collect().unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        let versions: Vec<Option<i32>> = df.column("version").unwrap().i32().unwrap().into_iter().collect();
+        assert_eq!(versions, vec![None, Some(1)]);
+
+        let structured: Vec<Option<&str>> = df.column("structured_data").unwrap().str().unwrap().into_iter().collect();
+        assert!(structured[0].is_none());
+        assert!(structured[1].unwrap().contains("exampleSDID@32473"));
+
+        let messages: Vec<Option<&str>> = df.column("message").unwrap().str().unwrap().into_iter().collect();
+        assert_eq!(messages[1], Some("password changed"));
+    }
+}
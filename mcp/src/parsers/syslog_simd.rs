@@ -6,7 +6,7 @@
 use memchr::memchr;
 use memmap2::Mmap;
 use rayon::prelude::*;
-use regex::bytes::Regex;
+use regex::bytes::{Regex, RegexSet};
 use std::collections::HashMap;
 use std::fs::File;
 use std::path::Path;
@@ -112,6 +112,119 @@ fn extract_message<'a>(line: &'a [u8], offsets: &SyslogOffsets) -> &'a [u8] {
     }
 }
 
+/// Three-letter month name to number (1-12), used to parse BSD timestamps.
+const MONTHS: [(&[u8], u8); 12] = [
+    (b"Jan", 1), (b"Feb", 2), (b"Mar", 3), (b"Apr", 4),
+    (b"May", 5), (b"Jun", 6), (b"Jul", 7), (b"Aug", 8),
+    (b"Sep", 9), (b"Oct", 10), (b"Nov", 11), (b"Dec", 12),
+];
+
+/// A BSD syslog timestamp reduced to a comparable tuple. Syslog carries no
+/// year, so this only sorts correctly within a single year -- callers
+/// filtering across a Dec->Jan rollover need to split the query in two.
+pub type BsdTimestamp = (u8, u8, u8, u8, u8);
+
+#[inline]
+fn parse_2digit(b: &[u8]) -> Option<u8> {
+    let d0 = b[0].wrapping_sub(b'0');
+    let d1 = b[1].wrapping_sub(b'0');
+    if d0 < 10 && d1 < 10 {
+        Some(d0 * 10 + d1)
+    } else {
+        None
+    }
+}
+
+/// Parse a space-padded day ("17" or " 7") into a number.
+#[inline]
+fn parse_day(b: &[u8]) -> Option<u8> {
+    let d1 = b[1].wrapping_sub(b'0');
+    if d1 >= 10 {
+        return None;
+    }
+    let d0 = if b[0] == b' ' {
+        0
+    } else {
+        let d = b[0].wrapping_sub(b'0');
+        if d >= 10 {
+            return None;
+        }
+        d
+    };
+    Some(d0 * 10 + d1)
+}
+
+/// Parse the BSD timestamp ("Mon DD HH:MM:SS") that sits right after the
+/// optional `<priority>` prefix into `(month, day, hour, min, sec)`.
+#[inline]
+pub(crate) fn parse_bsd_timestamp(line: &[u8]) -> Option<BsdTimestamp> {
+    let start = if !line.is_empty() && line[0] == b'<' {
+        memchr(b'>', line).map(|i| i + 1)?
+    } else {
+        0
+    };
+
+    if start + 15 > line.len() {
+        return None;
+    }
+    let ts = &line[start..start + 15];
+
+    let month = MONTHS
+        .iter()
+        .find(|(name, _)| *name == &ts[0..3])
+        .map(|(_, n)| *n)?;
+    let day = parse_day(&ts[4..6])?;
+    let hour = parse_2digit(&ts[7..9])?;
+    let min = parse_2digit(&ts[10..12])?;
+    let sec = parse_2digit(&ts[13..15])?;
+
+    Some((month, day, hour, min, sec))
+}
+
+/// Time range filter over BSD syslog timestamps.
+///
+/// Because syslog carries no year, bounds are compared on the
+/// `(month, day, hour, min, sec)` tuple alone; a window spanning a
+/// year boundary (e.g. Dec 31 -> Jan 1) will not behave as expected.
+#[derive(Debug, Clone, Copy)]
+pub struct SyslogTimeFilter {
+    pub start: Option<BsdTimestamp>,
+    pub end: Option<BsdTimestamp>,
+}
+
+impl SyslogTimeFilter {
+    /// Build a filter from "Mon DD HH:MM:SS" strings. Returns `None` if
+    /// neither bound is present or parseable.
+    pub fn new(start: Option<&str>, end: Option<&str>) -> Option<Self> {
+        let start_val = start.and_then(|s| parse_bsd_timestamp(s.trim().as_bytes()));
+        let end_val = end.and_then(|s| parse_bsd_timestamp(s.trim().as_bytes()));
+
+        if start_val.is_none() && end_val.is_none() {
+            return None;
+        }
+
+        Some(SyslogTimeFilter {
+            start: start_val,
+            end: end_val,
+        })
+    }
+
+    #[inline]
+    pub fn matches(&self, ts: BsdTimestamp) -> bool {
+        if let Some(start) = self.start {
+            if ts < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.end {
+            if ts > end {
+                return false;
+            }
+        }
+        true
+    }
+}
+
 /// Column to group by for syslog
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyslogGroupBy {
@@ -129,6 +242,90 @@ impl SyslogGroupBy {
     }
 }
 
+/// Syslog severity levels, mirroring `priority_to_level` in the polars
+/// parser (`priority % 8`), ordered from most to least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SyslogSeverity {
+    Emergency = 0,
+    Alert = 1,
+    Critical = 2,
+    Error = 3,
+    Warning = 4,
+    Notice = 5,
+    Info = 6,
+    Debug = 7,
+}
+
+impl SyslogSeverity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "emergency" | "emerg" => Some(SyslogSeverity::Emergency),
+            "alert" => Some(SyslogSeverity::Alert),
+            "critical" | "crit" => Some(SyslogSeverity::Critical),
+            "error" | "err" => Some(SyslogSeverity::Error),
+            "warning" | "warn" => Some(SyslogSeverity::Warning),
+            "notice" => Some(SyslogSeverity::Notice),
+            "info" => Some(SyslogSeverity::Info),
+            "debug" => Some(SyslogSeverity::Debug),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Keep lines whose priority-derived severity is `<= min_severity` (i.e.
+/// at least as severe). `allow_missing` decides what happens to lines
+/// without a `<priority>` prefix, which have no severity to compare.
+#[derive(Debug, Clone, Copy)]
+pub struct SeverityFilter {
+    pub min_severity: u8,
+    pub allow_missing: bool,
+}
+
+impl SeverityFilter {
+    pub fn new(min_severity: u8, allow_missing: bool) -> Self {
+        SeverityFilter {
+            min_severity,
+            allow_missing,
+        }
+    }
+
+    #[inline]
+    fn matches(&self, line: &[u8]) -> bool {
+        match extract_severity(line) {
+            Some(severity) => severity <= self.min_severity,
+            None => self.allow_missing,
+        }
+    }
+}
+
+/// Extract `priority % 8` from a `<priority>` prefix, if present.
+#[inline]
+fn extract_severity(line: &[u8]) -> Option<u8> {
+    if line.is_empty() || line[0] != b'<' {
+        return None;
+    }
+    let close = memchr(b'>', line)?;
+    let digits = &line[1..close];
+    if digits.is_empty() || digits.len() > 3 {
+        return None;
+    }
+
+    let mut pri: u32 = 0;
+    for &b in digits {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        pri = pri * 10 + (b - b'0') as u32;
+    }
+
+    Some((pri % 8) as u8)
+}
+
 /// Find chunk boundaries at newlines
 fn find_chunk_boundaries(data: &[u8], chunk_size: usize) -> Vec<usize> {
     let mut boundaries = vec![0];
@@ -149,10 +346,12 @@ fn find_chunk_boundaries(data: &[u8], chunk_size: usize) -> Vec<usize> {
     boundaries
 }
 
-/// Filter syslog lines by text pattern
+/// Filter syslog lines by text pattern, optional time range and severity
 pub fn filter_lines(
     path: &Path,
     text_pattern: Option<&[u8]>,
+    time_filter: Option<SyslogTimeFilter>,
+    severity_filter: Option<SeverityFilter>,
     limit: usize,
 ) -> Result<(usize, Vec<String>), ParseError> {
     let file = File::open(path)?;
@@ -177,10 +376,25 @@ pub fn filter_lines(
                     .unwrap_or(chunk.len());
                 let line = &chunk[pos..line_end];
 
-                let matches = match &text_finder {
-                    Some(finder) => finder.find(line).is_some(),
-                    None => true,
-                };
+                let mut matches = true;
+
+                if let Some(ref sfilter) = severity_filter {
+                    matches = sfilter.matches(line);
+                }
+
+                if matches {
+                    if let Some(ref tfilter) = time_filter {
+                        matches = parse_bsd_timestamp(line)
+                            .map(|ts| tfilter.matches(ts))
+                            .unwrap_or(false);
+                    }
+                }
+
+                if matches {
+                    if let Some(ref finder) = text_finder {
+                        matches = finder.find(line).is_some();
+                    }
+                }
 
                 if matches {
                     local_count += 1;
@@ -205,10 +419,37 @@ pub fn filter_lines(
     Ok((total_count, lines))
 }
 
-/// Regex search in syslog
+/// Filter lines across multiple syslog files, applying the same time/text
+/// filters as [`filter_lines`]. Files are scanned in order and scanning
+/// stops as soon as `limit` matching lines have been collected.
+pub fn filter_lines_multi(
+    paths: &[&Path],
+    text_pattern: Option<&[u8]>,
+    time_filter: Option<SyslogTimeFilter>,
+    severity_filter: Option<SeverityFilter>,
+    limit: usize,
+) -> Result<(usize, Vec<String>), ParseError> {
+    let mut total_count = 0usize;
+    let mut lines: Vec<String> = Vec::new();
+
+    for path in paths {
+        if lines.len() >= limit {
+            break;
+        }
+        let remaining = limit - lines.len();
+        let (count, mut file_lines) = filter_lines(path, text_pattern, time_filter, severity_filter, remaining)?;
+        total_count += count;
+        lines.append(&mut file_lines);
+    }
+
+    Ok((total_count, lines))
+}
+
+/// Regex search in syslog, optionally bounded to a time range
 pub fn regex_search(
     path: &Path,
     pattern: &str,
+    time_filter: Option<SyslogTimeFilter>,
     limit: usize,
 ) -> Result<(usize, Vec<String>), ParseError> {
     let file = File::open(path)?;
@@ -235,7 +476,14 @@ pub fn regex_search(
                     .unwrap_or(chunk.len());
                 let line = &chunk[pos..line_end];
 
-                if regex.is_match(line) {
+                let mut matches = true;
+                if let Some(ref tfilter) = time_filter {
+                    matches = parse_bsd_timestamp(line)
+                        .map(|ts| tfilter.matches(ts))
+                        .unwrap_or(false);
+                }
+
+                if matches && regex.is_match(line) {
                     local_count += 1;
                     local_lines.push(line);
                 }
@@ -258,11 +506,177 @@ pub fn regex_search(
     Ok((total_count, lines))
 }
 
+/// A line that matched one or more labeled patterns from a [`RegexSet`] scan.
+#[derive(Debug, Clone)]
+pub struct TaggedMatch {
+    pub line: String,
+    pub labels: Vec<String>,
+}
+
+/// Scan for many labeled signatures (e.g. `("sql-injection", r"union\s+select")`)
+/// in a single pass per line using a `regex::bytes::RegexSet`, rather than
+/// running one `regex_search` per signature.
+///
+/// Returns per-label hit counts plus a sample of matching lines (bounded by
+/// `limit`), each tagged with every label that fired on it.
+pub fn multi_pattern_search(
+    path: &Path,
+    patterns: &[(String, String)],
+    time_filter: Option<SyslogTimeFilter>,
+    limit: usize,
+) -> Result<(HashMap<String, u64>, Vec<TaggedMatch>), ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let labels: Vec<&str> = patterns.iter().map(|(label, _)| label.as_str()).collect();
+    let raw_patterns: Vec<&str> = patterns.iter().map(|(_, pattern)| pattern.as_str()).collect();
+    let set = RegexSet::new(&raw_patterns)
+        .map_err(|e| ParseError::ParseFailed(format!("Invalid regex set: {}", e)))?;
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    let results: Vec<(HashMap<String, u64>, Vec<TaggedMatch>)> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut local_counts: HashMap<String, u64> = HashMap::new();
+            let mut local_samples: Vec<TaggedMatch> = Vec::new();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                let mut matches = true;
+                if let Some(ref tfilter) = time_filter {
+                    matches = parse_bsd_timestamp(line)
+                        .map(|ts| tfilter.matches(ts))
+                        .unwrap_or(false);
+                }
+
+                if matches {
+                    let hits = set.matches(line);
+                    if hits.matched_any() {
+                        let matched_labels: Vec<String> =
+                            hits.into_iter().map(|i| labels[i].to_string()).collect();
+
+                        for label in &matched_labels {
+                            *local_counts.entry(label.clone()).or_insert(0) += 1;
+                        }
+
+                        if let Ok(text) = std::str::from_utf8(line) {
+                            local_samples.push(TaggedMatch {
+                                line: text.to_string(),
+                                labels: matched_labels,
+                            });
+                        }
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            (local_counts, local_samples)
+        })
+        .collect();
+
+    let mut global_counts: HashMap<String, u64> = HashMap::new();
+    let mut samples: Vec<TaggedMatch> = Vec::new();
+    for (counts, local_samples) in results {
+        for (label, count) in counts {
+            *global_counts.entry(label).or_insert(0) += count;
+        }
+        samples.extend(local_samples);
+    }
+    samples.truncate(limit);
+
+    Ok((global_counts, samples))
+}
+
+/// Search for many regex patterns in a single pass per line using a
+/// `regex::bytes::RegexSet`, rather than running one `regex_search` per
+/// pattern. Unlike [`multi_pattern_search`], this reports the total count
+/// of *distinct lines* matching at least one pattern (not a per-pattern
+/// tally), tagging each sampled line with the index of every pattern that
+/// fired on it.
+pub fn regex_search_multi(
+    path: &Path,
+    patterns: &[String],
+    time_filter: Option<SyslogTimeFilter>,
+    limit: usize,
+) -> Result<(usize, Vec<TaggedMatch>), ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let set = RegexSet::new(patterns)
+        .map_err(|e| ParseError::ParseFailed(format!("Invalid regex set: {}", e)))?;
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    let results: Vec<(usize, Vec<TaggedMatch>)> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut local_count = 0;
+            let mut local_samples: Vec<TaggedMatch> = Vec::new();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                let mut matches = true;
+                if let Some(ref tfilter) = time_filter {
+                    matches = parse_bsd_timestamp(line)
+                        .map(|ts| tfilter.matches(ts))
+                        .unwrap_or(false);
+                }
+
+                if matches {
+                    let hits = set.matches(line);
+                    if hits.matched_any() {
+                        local_count += 1;
+                        if let Ok(text) = std::str::from_utf8(line) {
+                            local_samples.push(TaggedMatch {
+                                line: text.to_string(),
+                                labels: hits.iter().map(|i| i.to_string()).collect(),
+                            });
+                        }
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            (local_count, local_samples)
+        })
+        .collect();
+
+    let total_count: usize = results.iter().map(|(c, _)| c).sum();
+    let samples: Vec<TaggedMatch> = results
+        .into_iter()
+        .flat_map(|(_, samples)| samples)
+        .take(limit)
+        .collect();
+
+    Ok((total_count, samples))
+}
+
 /// Group by hostname or process with count
 pub fn group_by_count(
     path: &Path,
     column: SyslogGroupBy,
     text_pattern: Option<&[u8]>,
+    time_filter: Option<SyslogTimeFilter>,
+    severity_filter: Option<SeverityFilter>,
 ) -> Result<Vec<(String, u64)>, ParseError> {
     let file = File::open(path)?;
     let mmap = unsafe { Mmap::map(&file)? };
@@ -285,10 +699,25 @@ pub fn group_by_count(
                     .unwrap_or(chunk.len());
                 let line = &chunk[pos..line_end];
 
-                let matches = match &text_finder {
-                    Some(finder) => finder.find(line).is_some(),
-                    None => true,
-                };
+                let mut matches = true;
+
+                if let Some(ref sfilter) = severity_filter {
+                    matches = sfilter.matches(line);
+                }
+
+                if matches {
+                    if let Some(ref tfilter) = time_filter {
+                        matches = parse_bsd_timestamp(line)
+                            .map(|ts| tfilter.matches(ts))
+                            .unwrap_or(false);
+                    }
+                }
+
+                if matches {
+                    if let Some(ref finder) = text_finder {
+                        matches = finder.find(line).is_some();
+                    }
+                }
 
                 if matches {
                     if let Some(offsets) = find_syslog_fields(line) {
@@ -327,6 +756,259 @@ pub fn group_by_count(
     Ok(result)
 }
 
+// ============================================================================
+// BRUTE-FORCE / INTRUSION DETECTION
+// ============================================================================
+
+/// Default failure signatures used when the caller doesn't supply their own.
+pub const DEFAULT_FAILURE_SIGNATURES: &[&str] = &[
+    "Failed password",
+    "authentication failure",
+    "Invalid user",
+];
+
+/// A source IP that tripped the brute-force threshold within `window_secs`.
+#[derive(Debug, Clone)]
+pub struct BruteForceOffender {
+    pub ip: std::net::IpAddr,
+    pub peak_burst: usize,
+    pub first_seen: u32,
+    pub last_seen: u32,
+    pub total_events: usize,
+}
+
+/// Days since the Unix epoch for a given y/m/d (Howard Hinnant's
+/// `days_from_civil` algorithm).
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400; // [0, 399]
+    let mp = (m + 9) % 12; // [0, 11]
+    let doy = (153 * mp + 2) / 5 + d - 1; // [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+    era * 146097 + doe - 719468
+}
+
+/// Convert a BSD timestamp tuple to epoch seconds using a caller-supplied
+/// base year. Syslog timestamps carry no year, so scans spanning a
+/// Dec->Jan rollover should be split and run once per year.
+fn bsd_timestamp_to_epoch(ts: BsdTimestamp, base_year: i32) -> u32 {
+    let (month, day, hour, min, sec) = ts;
+    let days = days_from_civil(base_year as i64, month as i64, day as i64);
+    (days * 86400 + hour as i64 * 3600 + min as i64 * 60 + sec as i64) as u32
+}
+
+/// Scan a syslog file for source IPs exhibiting repeated authentication
+/// failures within a sliding `window_secs` window (a fail2ban-style scan).
+///
+/// For each IP, timestamps of matching events are collected, sorted, and
+/// walked with a two-pointer sliding window: the window grows over sorted
+/// timestamps and shrinks from the left whenever it exceeds `window_secs`;
+/// an IP is flagged once the window holds at least `threshold` events.
+/// Offenders are returned sorted by peak burst size, descending.
+pub fn detect_brute_force(
+    path: &Path,
+    signatures: &[&str],
+    window_secs: u32,
+    threshold: usize,
+    base_year: i32,
+) -> Result<Vec<BruteForceOffender>, ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    let signature_finders: Vec<memchr::memmem::Finder> = signatures
+        .iter()
+        .map(|s| memchr::memmem::Finder::new(s.as_bytes()))
+        .collect();
+
+    let ip_regex = Regex::new(r"(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})")
+        .expect("static IPv4 regex is valid");
+
+    let local_maps: Vec<HashMap<std::net::IpAddr, Vec<u32>>> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut events: HashMap<std::net::IpAddr, Vec<u32>> = HashMap::new();
+            let mut pos = 0;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                let is_failure = signature_finders.iter().any(|f| f.find(line).is_some());
+
+                if is_failure {
+                    if let (Some(ts), Some(caps)) =
+                        (parse_bsd_timestamp(line), ip_regex.captures(line))
+                    {
+                        if let Some(ip_match) = caps.get(1) {
+                            if let Ok(ip_str) = std::str::from_utf8(ip_match.as_bytes()) {
+                                if let Ok(ip) = ip_str.parse::<std::net::IpAddr>() {
+                                    let epoch = bsd_timestamp_to_epoch(ts, base_year);
+                                    events.entry(ip).or_default().push(epoch);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                pos = line_end + 1;
+            }
+
+            events
+        })
+        .collect();
+
+    // Merge per-chunk maps exactly like `group_by_count` does
+    let mut global_events: HashMap<std::net::IpAddr, Vec<u32>> = HashMap::new();
+    for local in local_maps {
+        for (ip, mut timestamps) in local {
+            global_events.entry(ip).or_default().append(&mut timestamps);
+        }
+    }
+
+    let mut offenders = Vec::new();
+    for (ip, mut timestamps) in global_events {
+        timestamps.sort_unstable();
+
+        let mut left = 0;
+        let mut peak_burst = 0;
+        let mut peak_first = 0;
+        let mut peak_last = 0;
+
+        for right in 0..timestamps.len() {
+            while timestamps[right] - timestamps[left] > window_secs {
+                left += 1;
+            }
+            let burst = right - left + 1;
+            if burst > peak_burst {
+                peak_burst = burst;
+                peak_first = timestamps[left];
+                peak_last = timestamps[right];
+            }
+        }
+
+        if peak_burst >= threshold {
+            offenders.push(BruteForceOffender {
+                ip,
+                peak_burst,
+                first_seen: peak_first,
+                last_seen: peak_last,
+                total_events: timestamps.len(),
+            });
+        }
+    }
+
+    offenders.sort_by(|a, b| b.peak_burst.cmp(&a.peak_burst));
+
+    Ok(offenders)
+}
+
+// ============================================================================
+// TRANSACTION / SESSION RECONSTRUCTION
+// ============================================================================
+
+/// Source of the correlation key used to reassemble a chronological
+/// transcript out of interleaved lines.
+pub enum CorrelationKey<'a> {
+    /// Key on the `process[pid]` pair parsed by `find_syslog_fields`
+    /// (e.g. all lines from one `sshd[12345]` session).
+    ProcessPid,
+    /// Key on the first capture group of a user-supplied regex applied to
+    /// the message body (e.g. a Postfix queue ID).
+    Regex(&'a Regex),
+}
+
+/// Reassemble interleaved syslog lines into ordered per-correlation-key
+/// transcripts, keyed either by the `process[pid]` pair or by the first
+/// capture group of a caller-supplied regex.
+///
+/// The scan is chunked in parallel like `group_by_count`; each chunk
+/// records a line's position local to the chunk, and a sequential prefix
+/// sum over per-chunk line counts turns those into global line numbers so
+/// every group can be re-sorted into original file order afterward.
+pub fn correlate(
+    path: &Path,
+    key: CorrelationKey,
+) -> Result<HashMap<String, Vec<String>>, ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let chunk_size = 4 * 1024 * 1024;
+    let chunk_bounds = find_chunk_boundaries(data, chunk_size);
+
+    type LocalGroups<'a> = HashMap<Vec<u8>, Vec<(usize, &'a [u8])>>;
+
+    let chunk_results: Vec<(LocalGroups, usize)> = chunk_bounds
+        .par_windows(2)
+        .map(|window| {
+            let chunk = &data[window[0]..window[1]];
+            let mut groups: LocalGroups = HashMap::new();
+            let mut pos = 0;
+            let mut line_no = 0usize;
+
+            while pos < chunk.len() {
+                let line_end = memchr(b'\n', &chunk[pos..])
+                    .map(|i| pos + i)
+                    .unwrap_or(chunk.len());
+                let line = &chunk[pos..line_end];
+
+                let correlation_key: Option<Vec<u8>> = match key {
+                    // `process_start..message_start` spans "process[pid]: ",
+                    // which is exactly the process+pid pair we key on.
+                    CorrelationKey::ProcessPid => find_syslog_fields(line)
+                        .map(|offsets| line[offsets.process_start..offsets.message_start].to_vec()),
+                    CorrelationKey::Regex(re) => re
+                        .captures(line)
+                        .and_then(|caps| caps.get(1))
+                        .map(|m| m.as_bytes().to_vec()),
+                };
+
+                if let Some(k) = correlation_key {
+                    groups.entry(k).or_default().push((line_no, line));
+                }
+
+                line_no += 1;
+                pos = line_end + 1;
+            }
+
+            (groups, line_no)
+        })
+        .collect();
+
+    // Turn per-chunk-local line numbers into global ones via a sequential
+    // prefix sum over chunk line counts, then merge groups.
+    let mut global_groups: HashMap<Vec<u8>, Vec<(usize, &[u8])>> = HashMap::new();
+    let mut base = 0usize;
+    for (groups, line_count) in chunk_results {
+        for (k, entries) in groups {
+            let global_entries = global_groups.entry(k).or_default();
+            global_entries.extend(entries.into_iter().map(|(local, line)| (base + local, line)));
+        }
+        base += line_count;
+    }
+
+    let mut result: HashMap<String, Vec<String>> = HashMap::new();
+    for (key_bytes, mut entries) in global_groups {
+        entries.sort_by_key(|(line_no, _)| *line_no);
+        let key_str = String::from_utf8_lossy(&key_bytes).to_string();
+        let transcript: Vec<String> = entries
+            .into_iter()
+            .filter_map(|(_, line)| std::str::from_utf8(line).ok().map(|s| s.to_string()))
+            .collect();
+        result.insert(key_str, transcript);
+    }
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,4 +1030,157 @@ mod tests {
         assert_eq!(extract_hostname(line, &offsets), b"server01");
         assert_eq!(extract_process(line, &offsets), b"nginx");
     }
+
+    #[test]
+    fn test_parse_bsd_timestamp() {
+        let line = b"Dec 17 10:30:45 server01 sshd[12345]: Accepted password for user";
+        assert_eq!(parse_bsd_timestamp(line), Some((12, 17, 10, 30, 45)));
+
+        let with_priority = b"<134>Dec  7 09:05:00 server01 nginx: GET /index.html";
+        assert_eq!(parse_bsd_timestamp(with_priority), Some((12, 7, 9, 5, 0)));
+    }
+
+    #[test]
+    fn test_syslog_time_filter() {
+        let filter = SyslogTimeFilter::new(Some("Dec 17 09:00:00"), Some("Dec 17 11:30:00")).unwrap();
+
+        assert!(filter.matches((12, 17, 10, 30, 45)));
+        assert!(!filter.matches((12, 17, 8, 0, 0)));
+        assert!(!filter.matches((12, 17, 12, 0, 0)));
+    }
+
+    #[test]
+    fn test_extract_severity() {
+        // priority 35 -> facility 4, severity 35 % 8 = 3 (ERROR)
+        let line = b"<35>Dec 17 10:30:45 server01 sshd[12345]: auth failure";
+        assert_eq!(extract_severity(line), Some(3));
+
+        let no_priority = b"Dec 17 10:30:45 server01 sshd[12345]: auth failure";
+        assert_eq!(extract_severity(no_priority), None);
+    }
+
+    #[test]
+    fn test_severity_filter() {
+        let filter = SeverityFilter::new(SyslogSeverity::Warning.as_u8(), false);
+
+        // severity 3 (ERROR) is more severe than WARNING (4), so it passes
+        assert!(filter.matches(b"<35>Dec 17 10:30:45 server01 sshd[12345]: auth failure"));
+        // priority 14 -> severity 14 % 8 = 6 (INFO), less severe than WARNING, so it's dropped
+        assert!(!filter.matches(b"<14>Dec 17 10:30:45 server01 sshd[12345]: noise"));
+        // missing priority is dropped when allow_missing is false
+        assert!(!filter.matches(b"Dec 17 10:30:45 server01 sshd[12345]: no prefix"));
+    }
+
+    #[test]
+    fn test_bsd_timestamp_to_epoch_orders_chronologically() {
+        let earlier = bsd_timestamp_to_epoch((12, 17, 9, 0, 0), 2024);
+        let later = bsd_timestamp_to_epoch((12, 17, 11, 30, 0), 2024);
+        assert!(earlier < later);
+    }
+
+    #[test]
+    fn test_correlate_by_regex_capture() {
+        let data = b"Dec 17 10:00:00 mail postfix/smtp[1]: A1B2C3: to=<a@example.com>\n\
+Dec 17 10:00:01 mail postfix/qmgr[2]: Z9Y8X7: unrelated entry\n\
+Dec 17 10:00:02 mail postfix/smtp[1]: A1B2C3: status=sent\n";
+        let tmp_path = write_temp_syslog(data);
+
+        let re = Regex::new(r"([A-Z0-9]{6}):").unwrap();
+        let groups = correlate(&tmp_path, CorrelationKey::Regex(&re)).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        let transcript = groups.get("A1B2C3").expect("A1B2C3 group present");
+        assert_eq!(transcript.len(), 2);
+        assert!(transcript[0].contains("to=<a@example.com>"));
+        assert!(transcript[1].contains("status=sent"));
+    }
+
+    fn write_temp_syslog(data: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "syslog_simd_test_{}_{}.log",
+            std::process::id(),
+            data.len()
+        ));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_multi_pattern_search_tags_every_matching_label() {
+        let data = b"Dec 17 10:00:00 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:00:01 web nginx[2]: GET /index.html 200\n\
+Dec 17 10:00:02 web sshd[1]: Invalid user admin from 10.0.0.1, union select 1\n";
+        let tmp_path = write_temp_syslog(data);
+
+        let patterns = vec![
+            ("brute-force".to_string(), r"Failed password|Invalid user".to_string()),
+            ("sql-injection".to_string(), r"union\s+select".to_string()),
+        ];
+        let (counts, samples) = multi_pattern_search(&tmp_path, &patterns, None, 10).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        assert_eq!(counts.get("brute-force"), Some(&2));
+        assert_eq!(counts.get("sql-injection"), Some(&1));
+
+        let tagged_both = samples
+            .iter()
+            .find(|m| m.labels.len() == 2)
+            .expect("one line should be tagged with both labels");
+        assert!(tagged_both.labels.contains(&"brute-force".to_string()));
+        assert!(tagged_both.labels.contains(&"sql-injection".to_string()));
+    }
+
+    #[test]
+    fn test_regex_search_multi_counts_distinct_lines() {
+        let data = b"Dec 17 10:00:00 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:00:01 web nginx[2]: GET /index.html 200\n\
+Dec 17 10:00:02 web sshd[1]: Invalid user admin from 10.0.0.1, union select 1\n";
+        let tmp_path = write_temp_syslog(data);
+
+        let patterns = vec![
+            r"Failed password|Invalid user".to_string(),
+            r"union\s+select".to_string(),
+        ];
+        let (count, samples) = regex_search_multi(&tmp_path, &patterns, None, 10).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        // Two distinct lines matched at least one pattern, not three (one line fires both).
+        assert_eq!(count, 2);
+        let tagged_both = samples.iter().find(|m| m.labels.len() == 2)
+            .expect("one line should be tagged with both pattern indices");
+        assert_eq!(tagged_both.labels, vec!["0".to_string(), "1".to_string()]);
+    }
+
+    #[test]
+    fn test_detect_brute_force_flags_burst_within_window() {
+        let data = b"Dec 17 10:00:00 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:00:10 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:00:20 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:00:30 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:00:40 web sshd[1]: Failed password for root from 10.0.0.1\n";
+        let tmp_path = write_temp_syslog(data);
+
+        let offenders = detect_brute_force(&tmp_path, &["Failed password"], 60, 5, 2024).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        assert_eq!(offenders.len(), 1);
+        assert_eq!(offenders[0].ip, "10.0.0.1".parse::<std::net::IpAddr>().unwrap());
+        assert_eq!(offenders[0].peak_burst, 5);
+        assert_eq!(offenders[0].total_events, 5);
+    }
+
+    #[test]
+    fn test_detect_brute_force_not_flagged_when_spread_beyond_window() {
+        let data = b"Dec 17 10:00:00 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:05:00 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:10:00 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:15:00 web sshd[1]: Failed password for root from 10.0.0.1\n\
+Dec 17 10:20:00 web sshd[1]: Failed password for root from 10.0.0.1\n";
+        let tmp_path = write_temp_syslog(data);
+
+        let offenders = detect_brute_force(&tmp_path, &["Failed password"], 60, 5, 2024).unwrap();
+        std::fs::remove_file(&tmp_path).ok();
+
+        assert!(offenders.is_empty());
+    }
 }
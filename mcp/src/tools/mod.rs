@@ -6,9 +6,13 @@ use rmcp::ErrorData as McpError;
 use serde::Deserialize;
 use schemars::JsonSchema;
 use std::future::Future;
+use std::io::Read as _;
+use std::os::unix::fs::MetadataExt;
 
-use crate::parsers::{self, LogFormat, apache_simd, syslog_simd};
-use crate::engine::{QueryBuilder, query::dataframe_to_json, query::get_schema_info};
+use crate::parsers::{self, LogFormat, ParseError, apache_simd, syslog_simd};
+use crate::engine::{QueryBuilder, query::dataframe_to_json, query::get_schema_info, query::run_sql};
+use crate::engine::query::{bucket_series, detect_spikes, AggKind, OutputFormat, WindowClosed};
+use crate::engine::query::{dataframe_to_ipc, dataframe_to_parquet, QueryError};
 
 #[derive(Clone)]
 pub struct LogForensicsServer {
@@ -41,6 +45,19 @@ pub struct AnalyzeLogsParams {
     /// Maximum number of rows to return (default 50)
     #[serde(default = "default_limit")]
     pub limit: u32,
+    /// Glob patterns to exclude while walking (e.g. ["*.gz", "*.1"])
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Grok template for format "grok", e.g. "%{IP:client} %{WORD:method} %{NUMBER:status}"
+    pub grok_pattern: Option<String>,
+    /// Name of a saved grok pipeline (alternative to grok_pattern)
+    pub grok_name: Option<String>,
+    /// Strptime-style format for the timestamp column, used when filtering
+    /// by filter_time_start/filter_time_end (e.g. "%d/%b/%Y:%H:%M:%S %z" for
+    /// Apache/CLF logs). Defaults to the Apache/CLF format for apache/nginx,
+    /// or ISO8601 auto-detection otherwise; required for syslog's RFC 3164
+    /// timestamps since they're ambiguous without a format hint.
+    pub time_format: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -55,6 +72,22 @@ pub struct GetSchemaParams {
     pub sample_rows: u32,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DetectLogFormatParams {
+    /// Path to log file
+    pub path: String,
+}
+
+/// One aggregate to compute per group in an `aggregate_logs` call whose
+/// `metrics` field is set; see [`AggregateLogsParams::metrics`].
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AggMetricSpec {
+    /// Aggregation operation: "count", "sum", "avg", "min", "max", "unique"
+    pub operation: String,
+    /// Column to aggregate (ignored for "count")
+    pub column: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct AggregateLogsParams {
     /// Path to log file, directory, or glob pattern
@@ -65,6 +98,14 @@ pub struct AggregateLogsParams {
     pub column: Option<String>,
     /// Column to group by
     pub group_by: Option<String>,
+    /// Group by several columns at once (e.g. ["status", "remote_host"] for
+    /// "per status code, per host"); overrides group_by when non-empty
+    #[serde(default)]
+    pub group_by_cols: Vec<String>,
+    /// Compute several aggregates per group in one pass instead of one
+    /// `aggregate_logs` call per metric (e.g. count, avg bytes, and max
+    /// latency together). Overrides operation/column when present.
+    pub metrics: Option<Vec<AggMetricSpec>>,
     /// Filter by text pattern
     pub filter_text: Option<String>,
     /// Log format
@@ -73,6 +114,24 @@ pub struct AggregateLogsParams {
     /// Maximum rows to return
     #[serde(default = "default_limit")]
     pub limit: u32,
+    /// Glob patterns to exclude while walking (e.g. ["*.gz", "*.1"])
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Grok template for format "grok", e.g. "%{IP:client} %{WORD:method} %{NUMBER:status}"
+    pub grok_pattern: Option<String>,
+    /// Name of a saved grok pipeline (alternative to grok_pattern)
+    pub grok_name: Option<String>,
+    /// Restrict to lines whose request path matches at least one of these
+    /// globs (e.g. ["/api/*"]); empty means match every path. Applied only
+    /// on the Apache/Nginx fast path (count grouped by a built-in column, or
+    /// sum/avg/min/max); aggregations that fall back to the Polars path
+    /// ignore path_include and path_exclude.
+    #[serde(default)]
+    pub path_include: Vec<String>,
+    /// Exclude lines whose request path matches any of these globs (e.g.
+    /// ["/checkout/health"]), applied after path_include
+    #[serde(default)]
+    pub path_exclude: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -81,6 +140,11 @@ pub struct SearchPatternParams {
     pub path: String,
     /// Regex pattern to search for
     pub pattern: String,
+    /// Additional regex patterns to search for in the same pass (compiled into
+    /// one RegexSet/alternation so the file is only scanned once); `pattern`
+    /// is always included as pattern index 0.
+    #[serde(default)]
+    pub patterns: Vec<String>,
     /// Column to search in (searches all text columns if not specified)
     pub column: Option<String>,
     /// Case sensitive search (default false)
@@ -92,6 +156,19 @@ pub struct SearchPatternParams {
     /// Maximum rows to return
     #[serde(default = "default_limit")]
     pub limit: u32,
+    /// Glob patterns to exclude while walking (e.g. ["*.gz", "*.1"])
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Restrict to lines whose request path matches at least one of these
+    /// globs (e.g. ["/api/*"]); empty means match every path. Applied only
+    /// on the Apache/Nginx fast path, which searches a single file; other
+    /// searches ignore path_include and path_exclude.
+    #[serde(default)]
+    pub path_include: Vec<String>,
+    /// Exclude lines whose request path matches any of these globs (e.g.
+    /// ["/checkout/health"]), applied after path_include
+    #[serde(default)]
+    pub path_exclude: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -112,6 +189,200 @@ pub struct TimeAnalysisParams {
     /// Maximum buckets to return
     #[serde(default = "default_limit")]
     pub limit: u32,
+    /// Glob patterns to exclude while walking (e.g. ["*.gz", "*.1"])
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DetectSpikesParams {
+    /// Path to log file, directory, or glob pattern
+    pub path: String,
+    /// Time bucket: "minute", "hour", "day"
+    pub bucket: String,
+    /// Time column to use (auto-detected if not specified)
+    pub time_column: Option<String>,
+    /// Filter by text pattern
+    pub filter_text: Option<String>,
+    /// Log format
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// Number of preceding buckets used as the rolling baseline (default 5)
+    #[serde(default = "default_spike_window")]
+    pub window: u32,
+    /// Flag a bucket once its count exceeds mean + k*stddev of the baseline (default 3.0)
+    #[serde(default = "default_spike_k")]
+    pub k: f64,
+    /// Glob patterns to exclude while walking (e.g. ["*.gz", "*.1"])
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+fn default_spike_window() -> u32 { 5 }
+fn default_spike_k() -> f64 { 3.0 }
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CorrelateSessionsParams {
+    /// Path to log file (Apache/Nginx combined format)
+    pub path: String,
+    /// How to key related lines together: "ip" or "ip_ua" (ip + user agent, default)
+    #[serde(default = "default_correlation_key")]
+    pub key_by: String,
+    /// Split a key's activity into a new session once the gap since the
+    /// previous request exceeds this many minutes (default 30)
+    #[serde(default = "default_gap_minutes")]
+    pub gap_minutes: u32,
+    /// Sort sessions by "request_count" or "total_bytes" (default "request_count")
+    #[serde(default = "default_correlation_sort")]
+    pub sort_by: String,
+    /// Maximum sessions to return
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+fn default_correlation_key() -> String { "ip_ua".to_string() }
+fn default_gap_minutes() -> u32 { 30 }
+fn default_correlation_sort() -> String { "request_count".to_string() }
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiagnoseLogParsingParams {
+    /// Path to log file (Apache/Nginx combined format)
+    pub path: String,
+    /// Maximum number of rejected-line samples to return
+    #[serde(default = "default_diagnostics_sample_limit")]
+    pub sample_limit: u32,
+}
+
+fn default_diagnostics_sample_limit() -> u32 { 20 }
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct HistogramAggregateParams {
+    /// Path to log file, directory, or glob pattern
+    pub path: String,
+    /// Numeric field to bucket: "size" or "status"
+    pub field: String,
+    /// Bucket width (e.g. 1000 for size-in-bytes buckets, 100 for status-code buckets)
+    pub bucket_width: i64,
+    /// Bucket alignment offset (default 0); see histogram_bucket for how it's used
+    #[serde(default)]
+    pub offset: i64,
+    /// Filter by status code (e.g. ">=400", "500", "4xx")
+    pub filter_status: Option<String>,
+    /// Filter by text pattern
+    pub filter_text: Option<String>,
+    /// Glob patterns to exclude while walking (e.g. ["*.gz", "*.1"])
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct NumericRange {
+    /// Inclusive lower bound; omit for unbounded below
+    pub from: Option<i64>,
+    /// Exclusive upper bound; omit for unbounded above
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RangeAggregateParams {
+    /// Path to log file, directory, or glob pattern
+    pub path: String,
+    /// Numeric field to bucket: "size" or "status"
+    pub field: String,
+    /// Half-open [from, to) ranges to aggregate; ranges may overlap
+    pub ranges: Vec<NumericRange>,
+    /// Filter by status code (e.g. ">=400", "500", "4xx")
+    pub filter_status: Option<String>,
+    /// Filter by text pattern
+    pub filter_text: Option<String>,
+    /// Glob patterns to exclude while walking (e.g. ["*.gz", "*.1"])
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GroupByRegexParams {
+    /// Path to log file, directory, or glob pattern
+    pub path: String,
+    /// Regex with one or more named capture groups, e.g. "event=(?P<event>\\w+)
+    /// user=(?P<user>\\w+)"; the grouping key is the captures joined with "|"
+    pub pattern: String,
+    /// Filter by status code (e.g. ">=400", "500", "4xx")
+    pub filter_status: Option<String>,
+    /// Filter by text pattern
+    pub filter_text: Option<String>,
+    /// Restrict to lines whose request path matches at least one of these
+    /// globs (e.g. ["/api/*"]); empty means match every path
+    #[serde(default)]
+    pub path_include: Vec<String>,
+    /// Exclude lines whose request path matches any of these globs
+    #[serde(default)]
+    pub path_exclude: Vec<String>,
+    /// Maximum groups to return
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// Glob patterns to exclude while walking (e.g. ["*.gz", "*.1"])
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct QuerySqlParams {
+    /// Path to log file, directory, or glob pattern
+    pub path: String,
+    /// Log format
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// SQL query to run against the parsed logs, registered as table "logs"
+    pub sql: String,
+    /// Maximum number of rows to return (default 50)
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// If set, write the full (unlimited) result to this file instead of
+    /// inlining it as JSON — for persisting a query result as a columnar
+    /// evidence artifact. Format is chosen by output_format.
+    pub output_path: Option<String>,
+    /// Output file format when output_path is set: "parquet" (default),
+    /// "ipc"/"arrow"/"feather", or "json"
+    pub output_format: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TailLogsParams {
+    /// Path to the log file to tail (a single file, not a glob)
+    pub path: String,
+    /// Log format: "auto", "apache", "nginx", "syslog", "json", "csv"
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// Filter by status code (e.g., ">=400", "500", "4xx") - Apache/Nginx only
+    pub filter_status: Option<String>,
+    /// Filter by text pattern in newly appended lines
+    pub filter_text: Option<String>,
+    /// Regex pattern to match in newly appended lines
+    pub pattern: Option<String>,
+    /// Stop after collecting this many matches (default 100)
+    #[serde(default = "default_max_matches")]
+    pub max_matches: u32,
+    /// Stop after this many seconds even if max_matches isn't reached (default 10)
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_max_matches() -> u32 { 100 }
+fn default_timeout_secs() -> u64 { 10 }
+
+/// Extract the HTTP status code from a combined/common log line, e.g.
+/// `... "GET / HTTP/1.1" 200 1234 ...` -> `200`.
+fn extract_apache_status(line: &str) -> Option<u16> {
+    let quote_end = line.find("\" ")? + 2;
+    line[quote_end..].split_whitespace().next()?.parse().ok()
+}
+
+/// Resolve a grok template from either an inline pattern or a saved
+/// pipeline name, preferring the inline pattern when both are given.
+fn resolve_grok_template(pattern: Option<&str>, name: Option<&str>) -> Option<String> {
+    pattern.map(|p| p.to_string())
+        .or_else(|| name.and_then(parsers::grok::named_pipeline).map(|p| p.to_string()))
 }
 
 fn default_format() -> String { "auto".to_string() }
@@ -155,20 +426,20 @@ impl LogForensicsServer {
 
             // Convert text filter to bytes
             let text_pattern = params.filter_text.as_ref().map(|s| s.as_bytes());
+            let predicate = apache_simd::Predicate::from_options(status_filter, time_filter, text_pattern);
 
-            // Handle glob patterns
-            let paths = match parsers::expand_glob(&params.path) {
-                Ok(p) => p,
-                Err(_) => vec![path.to_path_buf()],
+            // Handle glob patterns, decompressing any matched archives
+            let scan = parsers::resolve_paths_for_scan(&params.path, &params.exclude).ok();
+            let paths: Vec<std::path::PathBuf> = match &scan {
+                Some(s) => s.paths().to_vec(),
+                None => vec![path.to_path_buf()],
             };
 
             // Use fast path for single file
             if paths.len() == 1 && paths[0].is_file() {
                 match apache_simd::filter_lines(
                     &paths[0],
-                    status_filter,
-                    time_filter,
-                    text_pattern,
+                    &predicate,
                     params.limit as usize,
                 ) {
                     Ok((count, lines)) => {
@@ -190,22 +461,28 @@ impl LogForensicsServer {
                     }
                 }
             } else if paths.len() > 1 {
-                // Multi-file: just count for now (can extend later)
-                if let Some(filter) = status_filter {
-                    let path_refs: Vec<&std::path::Path> = paths.iter().map(|p| p.as_path()).collect();
-                    match apache_simd::count_status_multi(&path_refs, filter) {
-                        Ok(count) => {
-                            let summary = format!(
-                                "Found {} rows matching status {} across {} files",
-                                count,
-                                params.filter_status.as_ref().unwrap(),
-                                paths.len()
-                            );
-                            return Ok(CallToolResult::success(vec![Content::text(summary)]));
-                        }
-                        Err(e) => {
-                            tracing::warn!("Multi-file fast path failed: {}", e);
-                        }
+                let path_refs: Vec<&std::path::Path> = paths.iter().map(|p| p.as_path()).collect();
+                match apache_simd::filter_lines_multi(
+                    &path_refs,
+                    &predicate,
+                    params.limit as usize,
+                ) {
+                    Ok((count, lines)) => {
+                        let json = serde_json::to_string(&lines).unwrap_or_default();
+                        let filter_desc = match (&params.filter_status, &params.filter_text) {
+                            (Some(s), Some(t)) => format!("status {} and text '{}'", s, t),
+                            (Some(s), None) => format!("status {}", s),
+                            (None, Some(t)) => format!("text '{}'", t),
+                            (None, None) => "all".to_string(),
+                        };
+                        let summary = format!(
+                            "Found {} rows matching {} across {} files (showing {})\n\nData:\n{}",
+                            count, filter_desc, paths.len(), lines.len(), json
+                        );
+                        return Ok(CallToolResult::success(vec![Content::text(summary)]));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Multi-file fast path failed: {}", e);
                     }
                 }
             }
@@ -215,16 +492,39 @@ impl LogForensicsServer {
         let is_syslog = matches!(format, LogFormat::Syslog)
             || (format == LogFormat::Auto && (params.path.contains("syslog") || params.path.contains("messages")));
 
-        if is_syslog
-            && params.filter_status.is_none()
-            && params.filter_time_start.is_none()
-            && params.filter_time_end.is_none()
-            && params.group_by.is_none()
-            && path.is_file()
-        {
+        // Only bounds that actually parse as BSD syslog time ("Mon DD
+        // HH:MM:SS") can be honored by this fast path; `SyslogTimeFilter::new`
+        // silently drops a bound it can't parse (e.g. an ISO timestamp, which
+        // AnalyzeLogsParams::filter_time_start's doc also accepts), which
+        // would otherwise make this fast path return every line as a match.
+        // Fall through to the regular (Polars) path instead when that happens.
+        let syslog_bounds_parse = params.filter_time_start.as_deref()
+            .map_or(true, |s| syslog_simd::parse_bsd_timestamp(s.trim().as_bytes()).is_some())
+            && params.filter_time_end.as_deref()
+            .map_or(true, |s| syslog_simd::parse_bsd_timestamp(s.trim().as_bytes()).is_some());
+
+        if is_syslog && params.filter_status.is_none() && params.group_by.is_none() && syslog_bounds_parse {
             let text_pattern = params.filter_text.as_ref().map(|s| s.as_bytes());
+            let time_filter = syslog_simd::SyslogTimeFilter::new(
+                params.filter_time_start.as_deref(),
+                params.filter_time_end.as_deref(),
+            );
+
+            let paths = match parsers::resolve_paths(&params.path, &params.exclude) {
+                Ok(p) => p,
+                Err(_) => vec![path.to_path_buf()],
+            };
+
+            let result = if paths.len() == 1 && paths[0].is_file() {
+                syslog_simd::filter_lines(&paths[0], text_pattern, time_filter, None, params.limit as usize)
+            } else if paths.len() > 1 {
+                let path_refs: Vec<&std::path::Path> = paths.iter().map(|p| p.as_path()).collect();
+                syslog_simd::filter_lines_multi(&path_refs, text_pattern, time_filter, None, params.limit as usize)
+            } else {
+                Err(ParseError::ParseFailed("No files found".to_string()))
+            };
 
-            match syslog_simd::filter_lines(path, text_pattern, params.limit as usize) {
+            match result {
                 Ok((count, lines)) => {
                     let json = serde_json::to_string(&lines).unwrap_or_default();
                     let filter_desc = params.filter_text.as_ref()
@@ -243,7 +543,12 @@ impl LogForensicsServer {
         }
 
         // REGULAR PATH
-        let lf = match parsers::parse_multiple(&params.path, format) {
+        let grok_template = resolve_grok_template(params.grok_pattern.as_deref(), params.grok_name.as_deref());
+        let lf = match &grok_template {
+            Some(template) => parsers::grok::parse_multiple(&params.path, template, &params.exclude),
+            None => parsers::parse_multiple(&params.path, format, &params.exclude),
+        };
+        let lf = match lf {
             Ok(lf) => lf,
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error parsing logs: {}", e))])),
         };
@@ -265,22 +570,35 @@ impl LogForensicsServer {
             qb = qb.filter_text(text_col, text_filter, false);
         }
 
-        if let Some(start) = &params.filter_time_start {
-            qb = qb.filter_time_range("timestamp", Some(start), None);
-        }
-        if let Some(end) = &params.filter_time_end {
-            qb = qb.filter_time_range("timestamp", None, Some(end));
+        if params.filter_time_start.is_some() || params.filter_time_end.is_some() {
+            let time_format = params.time_format.clone().or_else(|| match format {
+                LogFormat::Apache | LogFormat::Nginx => Some("%d/%b/%Y:%H:%M:%S %z".to_string()),
+                _ => None,
+            });
+            qb = match qb.filter_time_range(
+                "timestamp",
+                params.filter_time_start.as_deref(),
+                params.filter_time_end.as_deref(),
+                time_format.as_deref(),
+            ) {
+                Ok(qb) => qb,
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error applying time filter: {}", e))])),
+            };
         }
 
         // Group or sort
         if let Some(group_col) = &params.group_by {
-            qb = qb.group_by(group_col).count();
+            qb = if params.sort_desc {
+                qb.group_by(group_col).top_k(params.limit)
+            } else {
+                qb.group_by(group_col).bottom_k(params.limit)
+            };
         } else if let Some(sort_col) = &params.sort_by {
-            qb = qb.sort(sort_col, params.sort_desc);
+            qb = qb.top_k(sort_col, params.limit, params.sort_desc);
+        } else {
+            qb = qb.limit(params.limit);
         }
 
-        qb = qb.limit(params.limit);
-
         match qb.collect() {
             Ok(df) => {
                 let json = dataframe_to_json(&df).unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e));
@@ -292,6 +610,31 @@ impl LogForensicsServer {
         }
     }
 
+    #[tool(description = "Sample a log file and score it against each supported format (JSON, CSV, Apache/Nginx, syslog), returning the best match and the per-format confidence scores. Useful before querying with format=\"auto\" when the file's format isn't obvious from its name.")]
+    async fn detect_log_format(&self, Parameters(params): Parameters<DetectLogFormatParams>) -> Result<CallToolResult, McpError> {
+        let path = std::path::Path::new(&params.path);
+        match parsers::detect_format_with_confidence(path) {
+            Ok(result) => {
+                let json = serde_json::json!({
+                    "detected_format": format!("{:?}", result.format),
+                    "scores": {
+                        "json": result.scores.json,
+                        "csv": result.scores.csv,
+                        "apache": result.scores.apache,
+                        "syslog": result.scores.syslog,
+                    }
+                });
+                let summary = format!(
+                    "Detected format: {:?}\n\n{}",
+                    result.format,
+                    serde_json::to_string(&json).unwrap_or_default()
+                );
+                Ok(CallToolResult::success(vec![Content::text(summary)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error detecting format: {}", e))])),
+        }
+    }
+
     #[tool(description = "Get the schema and sample data from a log file. Use this first to understand what columns are available before querying.")]
     async fn get_log_schema(&self, Parameters(params): Parameters<GetSchemaParams>) -> Result<CallToolResult, McpError> {
         let format = LogFormat::from_str(&params.format);
@@ -320,7 +663,7 @@ impl LogForensicsServer {
         }
     }
 
-    #[tool(description = "Perform aggregations on log data: count, sum, avg, min, max, or unique counts. Group by any column for breakdowns.")]
+    #[tool(description = "Perform aggregations on log data: count, sum, avg, min, max, or unique counts. Group by any column for breakdowns, or group_by_cols for a multi-column breakdown (e.g. per status code, per host). Pass metrics to compute several aggregates per group in one pass instead of one call per metric. Use path_include/path_exclude to scope an Apache/Nginx aggregation to specific request paths (e.g. count 5xx under /checkout but not /checkout/health).")]
     async fn aggregate_logs(&self, Parameters(params): Parameters<AggregateLogsParams>) -> Result<CallToolResult, McpError> {
         let format = LogFormat::from_str(&params.format);
         let path = std::path::Path::new(&params.path);
@@ -331,6 +674,11 @@ impl LogForensicsServer {
 
         let op_lower = params.operation.to_lowercase();
 
+        let path_matcher = match apache_simd::PathMatcher::new(&params.path_include, &params.path_exclude) {
+            Ok(m) => m,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Invalid path_include/path_exclude glob: {}", e))])),
+        };
+
         if is_apache {
             // Check if we can use fast path for this group_by column
             let group_col = params.group_by.as_ref()
@@ -341,16 +689,18 @@ impl LogForensicsServer {
                 if let Some(column) = group_col {
                     let text_pattern = params.filter_text.as_ref().map(|s| s.as_bytes());
 
-                    let paths = match parsers::expand_glob(&params.path) {
-                        Ok(p) => p,
-                        Err(_) => vec![path.to_path_buf()],
+                    let scan = parsers::resolve_paths_for_scan(&params.path, &params.exclude).ok();
+                    let paths: Vec<std::path::PathBuf> = match &scan {
+                        Some(s) => s.paths().to_vec(),
+                        None => vec![path.to_path_buf()],
                     };
 
+                    let predicate = apache_simd::Predicate::from_options(None, None, text_pattern);
                     let result = if paths.len() == 1 && paths[0].is_file() {
-                        apache_simd::group_by_count(&paths[0], column, None, text_pattern)
+                        apache_simd::group_by_count(&paths[0], column, &predicate, Some(&path_matcher))
                     } else {
                         let path_refs: Vec<&std::path::Path> = paths.iter().map(|p| p.as_path()).collect();
-                        apache_simd::group_by_count_multi(&path_refs, column, None, text_pattern)
+                        apache_simd::group_by_count_multi(&path_refs, column, &predicate, Some(&path_matcher))
                     };
 
                     match result {
@@ -378,16 +728,17 @@ impl LogForensicsServer {
             if matches!(op_lower.as_str(), "sum" | "avg" | "min" | "max") {
                 let text_pattern = params.filter_text.as_ref().map(|s| s.as_bytes());
 
-                let paths = match parsers::expand_glob(&params.path) {
-                    Ok(p) => p,
-                    Err(_) => vec![path.to_path_buf()],
+                let scan = parsers::resolve_paths_for_scan(&params.path, &params.exclude).ok();
+                let paths: Vec<std::path::PathBuf> = match &scan {
+                    Some(s) => s.paths().to_vec(),
+                    None => vec![path.to_path_buf()],
                 };
 
                 let result = if paths.len() == 1 && paths[0].is_file() {
-                    apache_simd::aggregate_size(&paths[0], group_col, None, text_pattern)
+                    apache_simd::aggregate_size(&paths[0], group_col, None, text_pattern, Some(&path_matcher))
                 } else {
                     let path_refs: Vec<&std::path::Path> = paths.iter().map(|p| p.as_path()).collect();
-                    apache_simd::aggregate_size_multi(&path_refs, group_col, None, text_pattern)
+                    apache_simd::aggregate_size_multi(&path_refs, group_col, None, text_pattern, Some(&path_matcher))
                 };
 
                 match result {
@@ -432,14 +783,14 @@ impl LogForensicsServer {
             if let Some(column) = group_col {
                 let text_pattern = params.filter_text.as_ref().map(|s| s.as_bytes());
 
-                let paths = match parsers::expand_glob(&params.path) {
+                let paths = match parsers::resolve_paths(&params.path, &params.exclude) {
                     Ok(p) => p,
                     Err(_) => vec![path.to_path_buf()],
                 };
 
                 // Only use fast path for single files
                 if paths.len() == 1 && paths[0].is_file() {
-                    match syslog_simd::group_by_count(&paths[0], column, text_pattern) {
+                    match syslog_simd::group_by_count(&paths[0], column, text_pattern, None, None) {
                         Ok(results) => {
                             let col_name = params.group_by.as_ref().unwrap();
                             let limited: Vec<_> = results.into_iter().take(params.limit as usize).collect();
@@ -462,7 +813,12 @@ impl LogForensicsServer {
         }
 
         // REGULAR PATH
-        let lf = match parsers::parse_multiple(&params.path, format) {
+        let grok_template = resolve_grok_template(params.grok_pattern.as_deref(), params.grok_name.as_deref());
+        let lf = match &grok_template {
+            Some(template) => parsers::grok::parse_multiple(&params.path, template, &params.exclude),
+            None => parsers::parse_multiple(&params.path, format, &params.exclude),
+        };
+        let lf = match lf {
             Ok(lf) => lf,
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error parsing logs: {}", e))])),
         };
@@ -473,8 +829,34 @@ impl LogForensicsServer {
             qb = qb.filter_text("message", text_filter, false);
         }
 
-        let result_qb = if let Some(group_col) = &params.group_by {
-            let gb = qb.group_by(group_col);
+        let group_cols: Vec<&str> = if !params.group_by_cols.is_empty() {
+            params.group_by_cols.iter().map(|s| s.as_str()).collect()
+        } else if let Some(g) = &params.group_by {
+            vec![g.as_str()]
+        } else {
+            vec![]
+        };
+
+        let result_qb = if let Some(metrics) = &params.metrics {
+            if group_cols.is_empty() {
+                return Ok(CallToolResult::error(vec![Content::text("group_by or group_by_cols is required for aggregations".to_string())]));
+            }
+            let mut specs: Vec<(AggKind, &str)> = Vec::with_capacity(metrics.len());
+            for metric in metrics {
+                let kind = match metric.operation.to_lowercase().as_str() {
+                    "count" => AggKind::Count,
+                    "sum" => AggKind::Sum,
+                    "avg" => AggKind::Avg,
+                    "min" => AggKind::Min,
+                    "max" => AggKind::Max,
+                    "unique" => AggKind::UniqueCount,
+                    _ => return Ok(CallToolResult::error(vec![Content::text(format!("Unknown operation: {}", metric.operation))])),
+                };
+                specs.push((kind, metric.column.as_deref().unwrap_or("size")));
+            }
+            qb.group_by_many(&group_cols).agg(&specs)
+        } else if !group_cols.is_empty() {
+            let gb = qb.group_by_many(&group_cols);
             match params.operation.to_lowercase().as_str() {
                 "count" => gb.count(),
                 "sum" => {
@@ -506,9 +888,10 @@ impl LogForensicsServer {
         match result_qb.limit(params.limit).collect() {
             Ok(df) => {
                 let json = dataframe_to_json(&df).unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e));
+                let op_desc = if params.metrics.is_some() { "multiple metrics".to_string() } else { params.operation.clone() };
                 let summary = format!("Aggregation: {} by {}\n\n{}",
-                    params.operation,
-                    params.group_by.as_deref().unwrap_or("all"),
+                    op_desc,
+                    group_cols.join(", "),
                     json
                 );
                 Ok(CallToolResult::success(vec![Content::text(summary)]))
@@ -517,22 +900,223 @@ impl LogForensicsServer {
         }
     }
 
-    #[tool(description = "Search for regex patterns in log files. Returns matching rows with full context.")]
+    #[tool(description = "Bucket a numeric field (size or status) from an Apache/Nginx log into fixed-width histogram buckets and return a contiguous, sorted (bucket_lower_bound, count) series suitable for plotting a response-size or status-code distribution.")]
+    async fn histogram_aggregate(&self, Parameters(params): Parameters<HistogramAggregateParams>) -> Result<CallToolResult, McpError> {
+        let field = match apache_simd::HistogramField::parse(&params.field) {
+            Some(f) => f,
+            None => return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown field \"{}\": expected \"size\" or \"status\"", params.field
+            ))])),
+        };
+
+        let status_filter = params.filter_status.as_ref()
+            .and_then(|s| apache_simd::StatusFilter::parse(s));
+        let text_pattern = params.filter_text.as_ref().map(|s| s.as_bytes());
+
+        let scan = match parsers::resolve_paths_for_scan(&params.path, &params.exclude) {
+            Ok(s) => s,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error resolving path: {}", e))])),
+        };
+        let paths = scan.paths();
+
+        let result = if paths.len() == 1 && paths[0].is_file() {
+            apache_simd::histogram_aggregate(&paths[0], field, params.bucket_width, params.offset, status_filter, text_pattern)
+        } else {
+            let path_refs: Vec<&std::path::Path> = paths.iter().map(|p| p.as_path()).collect();
+            apache_simd::histogram_aggregate_multi(&path_refs, field, params.bucket_width, params.offset, status_filter, text_pattern)
+        };
+
+        match result {
+            Ok(series) => {
+                let json_data: Vec<serde_json::Value> = series.iter()
+                    .map(|(bucket, count)| serde_json::json!({"bucket": bucket, "count": count}))
+                    .collect();
+                let json = serde_json::to_string(&json_data).unwrap_or_default();
+                let summary = format!(
+                    "Histogram of {} (bucket_width={}, offset={}): {} bucket(s)\n\n{}",
+                    params.field, params.bucket_width, params.offset, json_data.len(), json
+                );
+                Ok(CallToolResult::success(vec![Content::text(summary)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error computing histogram: {}", e))])),
+        }
+    }
+
+    #[tool(description = "Aggregate a numeric field (size or status) from an Apache/Nginx log into user-defined, possibly-overlapping [from, to) ranges, returning sum/count/min/max per range. Useful for reports like \"bytes served for 1xx/2xx/3xx/4xx/5xx\" or \"requests with size in [0,1KB), [1KB,1MB), [1MB,∞)\" in one pass.")]
+    async fn range_aggregate(&self, Parameters(params): Parameters<RangeAggregateParams>) -> Result<CallToolResult, McpError> {
+        let field = match apache_simd::HistogramField::parse(&params.field) {
+            Some(f) => f,
+            None => return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown field \"{}\": expected \"size\" or \"status\"", params.field
+            ))])),
+        };
+
+        let ranges: Vec<(Option<i64>, Option<i64>)> = params.ranges.iter().map(|r| (r.from, r.to)).collect();
+        let status_filter = params.filter_status.as_ref()
+            .and_then(|s| apache_simd::StatusFilter::parse(s));
+        let text_pattern = params.filter_text.as_ref().map(|s| s.as_bytes());
+
+        let scan = match parsers::resolve_paths_for_scan(&params.path, &params.exclude) {
+            Ok(s) => s,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error resolving path: {}", e))])),
+        };
+        let paths = scan.paths();
+
+        let result = if paths.len() == 1 && paths[0].is_file() {
+            apache_simd::range_aggregate(&paths[0], field, &ranges, status_filter, text_pattern)
+        } else {
+            let path_refs: Vec<&std::path::Path> = paths.iter().map(|p| p.as_path()).collect();
+            apache_simd::range_aggregate_multi(&path_refs, field, &ranges, status_filter, text_pattern)
+        };
+
+        match result {
+            Ok(aggs) => {
+                let json_data: Vec<serde_json::Value> = params.ranges.iter().zip(aggs.iter())
+                    .map(|(range, agg)| serde_json::json!({
+                        "from": range.from,
+                        "to": range.to,
+                        "count": agg.count,
+                        "sum": agg.sum,
+                        "avg": agg.avg(),
+                        "min": if agg.min == i64::MAX { serde_json::Value::Null } else { serde_json::json!(agg.min) },
+                        "max": if agg.max == i64::MIN { serde_json::Value::Null } else { serde_json::json!(agg.max) },
+                    }))
+                    .collect();
+                let json = serde_json::to_string(&json_data).unwrap_or_default();
+                let summary = format!("Range aggregation of {} ({} range(s))\n\n{}", params.field, json_data.len(), json);
+                Ok(CallToolResult::success(vec![Content::text(summary)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error computing range aggregation: {}", e))])),
+        }
+    }
+
+    #[tool(description = "Group and count Apache/Nginx log lines by a user regex's named capture groups, for layouts GroupByColumn doesn't model natively (JSON-ish logs, custom delimiters, URL query parameters). The grouping key is every named capture joined with \"|\".")]
+    async fn group_by_regex(&self, Parameters(params): Parameters<GroupByRegexParams>) -> Result<CallToolResult, McpError> {
+        let status_filter = params.filter_status.as_ref()
+            .and_then(|s| apache_simd::StatusFilter::parse(s));
+        let text_pattern = params.filter_text.as_ref().map(|s| s.as_bytes());
+        let predicate = apache_simd::Predicate::from_options(status_filter, None, text_pattern);
+
+        let path_matcher = match apache_simd::PathMatcher::new(&params.path_include, &params.path_exclude) {
+            Ok(m) => m,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Invalid path_include/path_exclude glob: {}", e))])),
+        };
+
+        let scan = match parsers::resolve_paths_for_scan(&params.path, &params.exclude) {
+            Ok(s) => s,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error resolving path: {}", e))])),
+        };
+        let paths = scan.paths();
+
+        let result = if paths.len() == 1 && paths[0].is_file() {
+            apache_simd::group_by_regex(&paths[0], &params.pattern, &predicate, Some(&path_matcher))
+        } else {
+            let path_refs: Vec<&std::path::Path> = paths.iter().map(|p| p.as_path()).collect();
+            apache_simd::group_by_regex_multi(&path_refs, &params.pattern, &predicate, Some(&path_matcher))
+        };
+
+        match result {
+            Ok(results) => {
+                let limited: Vec<_> = results.into_iter().take(params.limit as usize).collect();
+                let json_data: Vec<serde_json::Value> = limited.iter()
+                    .map(|(key, count)| serde_json::json!({"key": key, "count": count}))
+                    .collect();
+                let json = serde_json::to_string(&json_data).unwrap_or_default();
+                let summary = format!("Group by regex captures ({} group(s))\n\n{}", json_data.len(), json);
+                Ok(CallToolResult::success(vec![Content::text(summary)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error grouping by regex: {}", e))])),
+        }
+    }
+
+    #[tool(description = "Run an arbitrary SQL query against parsed logs, registered as table \"logs\". Supports joins, window functions, and CASE expressions beyond what analyze_logs/aggregate_logs can express. Set output_path to persist the full result as a Parquet/IPC/JSON file instead of inlining it.")]
+    async fn query_sql(&self, Parameters(params): Parameters<QuerySqlParams>) -> Result<CallToolResult, McpError> {
+        let format = LogFormat::from_str(&params.format);
+
+        let lf = match parsers::parse_multiple(&params.path, format, &[]) {
+            Ok(lf) => lf,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error parsing logs: {}", e))])),
+        };
+
+        match run_sql(lf, "logs", &params.sql) {
+            Ok(df) => {
+                if let Some(output_path) = &params.output_path {
+                    let format_str = params.output_format.as_deref().unwrap_or("parquet");
+                    let output_format = match OutputFormat::parse(format_str) {
+                        Some(f) => f,
+                        None => return Ok(CallToolResult::error(vec![Content::text(format!("Unknown output_format: {}", format_str))])),
+                    };
+                    let write_result = match output_format {
+                        OutputFormat::Json => dataframe_to_json(&df).and_then(|json| {
+                            std::fs::write(output_path, json).map_err(|e| QueryError::InvalidQuery(e.to_string()))
+                        }),
+                        OutputFormat::Parquet => std::fs::File::create(output_path)
+                            .map_err(|e| QueryError::InvalidQuery(e.to_string()))
+                            .and_then(|file| dataframe_to_parquet(&df, file)),
+                        OutputFormat::Ipc => std::fs::File::create(output_path)
+                            .map_err(|e| QueryError::InvalidQuery(e.to_string()))
+                            .and_then(|file| dataframe_to_ipc(&df, file)),
+                    };
+                    return match write_result {
+                        Ok(()) => Ok(CallToolResult::success(vec![Content::text(format!(
+                            "SQL query returned {} rows, {} columns; wrote {} as {}",
+                            df.height(), df.width(), output_path, format_str
+                        ))])),
+                        Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Error writing {}: {}", output_path, e))])),
+                    };
+                }
+
+                let limited = df.head(Some(params.limit as usize));
+                let json = dataframe_to_json(&limited).unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e));
+                let summary = format!("SQL query returned {} rows (showing {})\n\nData:\n{}",
+                    df.height(),
+                    limited.height(),
+                    json
+                );
+                Ok(CallToolResult::success(vec![Content::text(summary)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("SQL error: {}", e))])),
+        }
+    }
+
+    #[tool(description = "Search for one or more regex patterns in log files in a single pass (pass extra patterns via `patterns`). Returns matching rows with full context, each tagged with which pattern(s) matched. Use path_include/path_exclude to scope an Apache/Nginx search to specific request paths.")]
     async fn search_pattern(&self, Parameters(params): Parameters<SearchPatternParams>) -> Result<CallToolResult, McpError> {
         let format = LogFormat::from_str(&params.format);
         let path = std::path::Path::new(&params.path);
 
+        let full_patterns: Vec<String> = std::iter::once(params.pattern.clone())
+            .chain(params.patterns.iter().cloned())
+            .collect();
+        let multi = full_patterns.len() > 1;
+
         // SIMD FAST PATH for Apache/Nginx
         let is_apache = matches!(format, LogFormat::Apache | LogFormat::Nginx)
             || (format == LogFormat::Auto && params.path.contains("access"));
 
         if is_apache && path.is_file() {
-            match apache_simd::regex_search(path, &params.pattern, None, params.limit as usize) {
-                Ok((count, lines)) => {
-                    let json = serde_json::to_string(&lines).unwrap_or_default();
+            let path_matcher = match apache_simd::PathMatcher::new(&params.path_include, &params.path_exclude) {
+                Ok(m) => m,
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Invalid path_include/path_exclude glob: {}", e))])),
+            };
+
+            let result = if multi {
+                apache_simd::regex_search_multi(path, &full_patterns, None, params.limit as usize, Some(&path_matcher))
+                    .map(|(count, matches)| {
+                        let tagged: Vec<serde_json::Value> = matches.iter()
+                            .map(|m| serde_json::json!({"line": m.line, "patterns": m.labels}))
+                            .collect();
+                        (count, serde_json::to_string(&tagged).unwrap_or_default())
+                    })
+            } else {
+                apache_simd::regex_search(path, &params.pattern, None, params.limit as usize, Some(&path_matcher))
+                    .map(|(count, lines)| (count, serde_json::to_string(&lines).unwrap_or_default()))
+            };
+
+            match result {
+                Ok((count, json)) => {
                     let summary = format!(
-                        "Found {} matches for pattern '{}' (SIMD fast path)\n\nData:\n{}",
-                        count, params.pattern, json
+                        "Found {} matches for {} pattern(s) (SIMD fast path)\n\nData:\n{}",
+                        count, full_patterns.len(), json
                     );
                     return Ok(CallToolResult::success(vec![Content::text(summary)]));
                 }
@@ -547,12 +1131,24 @@ impl LogForensicsServer {
             || (format == LogFormat::Auto && (params.path.contains("syslog") || params.path.contains("messages")));
 
         if is_syslog && path.is_file() {
-            match syslog_simd::regex_search(path, &params.pattern, params.limit as usize) {
-                Ok((count, lines)) => {
-                    let json = serde_json::to_string(&lines).unwrap_or_default();
+            let result = if multi {
+                syslog_simd::regex_search_multi(path, &full_patterns, None, params.limit as usize)
+                    .map(|(count, matches)| {
+                        let tagged: Vec<serde_json::Value> = matches.iter()
+                            .map(|m| serde_json::json!({"line": m.line, "patterns": m.labels}))
+                            .collect();
+                        (count, serde_json::to_string(&tagged).unwrap_or_default())
+                    })
+            } else {
+                syslog_simd::regex_search(path, &params.pattern, None, params.limit as usize)
+                    .map(|(count, lines)| (count, serde_json::to_string(&lines).unwrap_or_default()))
+            };
+
+            match result {
+                Ok((count, json)) => {
                     let summary = format!(
-                        "Found {} matches for pattern '{}' (SIMD fast path)\n\nData:\n{}",
-                        count, params.pattern, json
+                        "Found {} matches for {} pattern(s) (SIMD fast path)\n\nData:\n{}",
+                        count, full_patterns.len(), json
                     );
                     return Ok(CallToolResult::success(vec![Content::text(summary)]));
                 }
@@ -563,7 +1159,7 @@ impl LogForensicsServer {
         }
 
         // REGULAR PATH (Polars)
-        let lf = match parsers::parse_multiple(&params.path, format) {
+        let lf = match parsers::parse_multiple(&params.path, format, &params.exclude) {
             Ok(lf) => lf,
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error parsing logs: {}", e))])),
         };
@@ -575,16 +1171,23 @@ impl LogForensicsServer {
             _ => "raw",
         };
         let search_col = params.column.as_deref().unwrap_or(default_col);
-        let qb = QueryBuilder::new(lf)
-            .filter_regex(search_col, &params.pattern)
-            .limit(params.limit);
+        let qb = if multi {
+            QueryBuilder::new(lf)
+                .filter_regex_any(search_col, &full_patterns)
+                .annotate_regex_matches(search_col, &full_patterns)
+                .limit(params.limit)
+        } else {
+            QueryBuilder::new(lf)
+                .filter_regex(search_col, &params.pattern)
+                .limit(params.limit)
+        };
 
         match qb.collect() {
             Ok(df) => {
                 let json = dataframe_to_json(&df).unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e));
-                let summary = format!("Found {} matches for pattern '{}'\n\n{}",
+                let summary = format!("Found {} matches for {} pattern(s)\n\n{}",
                     df.height(),
-                    params.pattern,
+                    full_patterns.len(),
                     json
                 );
                 Ok(CallToolResult::success(vec![Content::text(summary)]))
@@ -597,14 +1200,28 @@ impl LogForensicsServer {
     async fn time_analysis(&self, Parameters(params): Parameters<TimeAnalysisParams>) -> Result<CallToolResult, McpError> {
         let format = LogFormat::from_str(&params.format);
 
-        let lf = match parsers::parse_multiple(&params.path, format) {
+        let lf = match parsers::parse_multiple(&params.path, format, &params.exclude) {
             Ok(lf) => lf,
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error parsing logs: {}", e))])),
         };
 
         let time_col = params.time_column.as_deref().unwrap_or("timestamp");
+        let every = match params.bucket.as_str() {
+            "minute" => "1m",
+            "hour" => "1h",
+            "day" => "1d",
+            other => other,
+        };
+        let time_format = match format {
+            LogFormat::Apache | LogFormat::Nginx => Some("%d/%b/%Y:%H:%M:%S %z"),
+            _ => None,
+        };
 
-        let qb = QueryBuilder::new(lf).group_by(time_col).count().limit(params.limit);
+        let gb = match QueryBuilder::new(lf).group_by_dynamic(time_col, every, every, "0", WindowClosed::Left, time_format) {
+            Ok(gb) => gb,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error bucketing time: {}", e))])),
+        };
+        let qb = gb.count().limit(params.limit);
 
         match qb.collect() {
             Ok(df) => {
@@ -619,6 +1236,247 @@ impl LogForensicsServer {
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!("Query error: {}", e))])),
         }
     }
+
+    #[tool(description = "Bucket logs over time and flag anomalous buckets whose count exceeds a rolling mean + k*stddev baseline (default k=3), surfacing traffic surges or error bursts instead of a raw count table. Returns each flagged bucket's timestamp, observed count, baseline mean/stddev, and z-score.")]
+    async fn detect_spikes(&self, Parameters(params): Parameters<DetectSpikesParams>) -> Result<CallToolResult, McpError> {
+        let format = LogFormat::from_str(&params.format);
+
+        let lf = match parsers::parse_multiple(&params.path, format, &params.exclude) {
+            Ok(lf) => lf,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error parsing logs: {}", e))])),
+        };
+
+        let time_col = params.time_column.as_deref().unwrap_or("timestamp");
+        let every = match params.bucket.as_str() {
+            "minute" => "1m",
+            "hour" => "1h",
+            "day" => "1d",
+            other => other,
+        };
+        let time_format = match format {
+            LogFormat::Apache | LogFormat::Nginx => Some("%d/%b/%Y:%H:%M:%S %z"),
+            _ => None,
+        };
+
+        let gb = match QueryBuilder::new(lf).group_by_dynamic(time_col, every, every, "0", WindowClosed::Left, time_format) {
+            Ok(gb) => gb,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error bucketing time: {}", e))])),
+        };
+        let qb = gb.count();
+
+        let df = match qb.collect() {
+            Ok(df) => df,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Query error: {}", e))])),
+        };
+
+        let buckets = match bucket_series(&df, time_col, "count") {
+            Ok(b) => b,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Query error: {}", e))])),
+        };
+
+        let spikes = detect_spikes(&buckets, params.window as usize, params.k);
+
+        let json_data: Vec<serde_json::Value> = spikes.iter()
+            .map(|s| serde_json::json!({
+                "bucket": s.bucket,
+                "count": s.count,
+                "baseline_mean": s.baseline_mean,
+                "baseline_stddev": s.baseline_stddev,
+                "z_score": s.z_score,
+            }))
+            .collect();
+        let json = serde_json::to_string(&json_data).unwrap_or_default();
+        let summary = format!(
+            "Spike detection by {} ({}, window={}, k={}): {} anomalous bucket(s) out of {}\n\n{}",
+            time_col, params.bucket, params.window, params.k, spikes.len(), buckets.len(), json
+        );
+        Ok(CallToolResult::success(vec![Content::text(summary)]))
+    }
+
+    #[tool(description = "Reconstruct multi-request sessions from an Apache/Nginx log by grouping lines from the same client (by IP, or IP+user-agent) and splitting their activity into sessions wherever the gap between requests exceeds gap_minutes. Returns each session's time range, request count, total bytes, distinct paths, and status-code histogram — useful for spotting the noisiest clients or reconstructing a scraper's crawl.")]
+    async fn correlate_sessions(&self, Parameters(params): Parameters<CorrelateSessionsParams>) -> Result<CallToolResult, McpError> {
+        let path = std::path::Path::new(&params.path);
+
+        let key_kind = match apache_simd::CorrelationKey::parse(&params.key_by) {
+            Some(k) => k,
+            None => return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown key_by \"{}\": expected \"ip\" or \"ip_ua\"", params.key_by
+            ))])),
+        };
+
+        let gap_seconds = (params.gap_minutes as i64) * 60;
+
+        let mut sessions = match apache_simd::correlate(path, key_kind, gap_seconds) {
+            Ok(s) => s,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error correlating sessions: {}", e))])),
+        };
+
+        match params.sort_by.as_str() {
+            "total_bytes" => sessions.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes)),
+            _ => sessions.sort_by(|a, b| b.request_count.cmp(&a.request_count)),
+        }
+
+        let total_sessions = sessions.len();
+        let limited: Vec<_> = sessions.into_iter().take(params.limit as usize).collect();
+
+        let json_data: Vec<serde_json::Value> = limited.iter()
+            .map(|s| serde_json::json!({
+                "key": s.key,
+                "start": s.start,
+                "end": s.end,
+                "request_count": s.request_count,
+                "total_bytes": s.total_bytes,
+                "distinct_paths": s.distinct_paths,
+                "status_histogram": s.status_histogram,
+            }))
+            .collect();
+        let json = serde_json::to_string(&json_data).unwrap_or_default();
+        let summary = format!(
+            "Correlated {} session(s) (showing {}), keyed by {}, gap threshold {}m\n\n{}",
+            total_sessions, json_data.len(), params.key_by, params.gap_minutes, json
+        );
+        Ok(CallToolResult::success(vec![Content::text(summary)]))
+    }
+
+    #[tool(description = "Diagnose why analyze_logs/aggregate_logs might be returning fewer rows than expected for an Apache/Nginx combined-log file: scans every line, tallying how many parsed vs. were rejected and why (e.g. missing request quote, unterminated timestamp bracket), plus a capped sample of the rejected lines themselves. A large rejected count dominated by one fault usually means the wrong LogFormat; a small one usually means a handful of genuinely corrupt lines.")]
+    async fn diagnose_log_parsing(&self, Parameters(params): Parameters<DiagnoseLogParsingParams>) -> Result<CallToolResult, McpError> {
+        let path = std::path::Path::new(&params.path);
+
+        let report = match apache_simd::scan_with_diagnostics(path, params.sample_limit as usize) {
+            Ok(r) => r,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error diagnosing log: {}", e))])),
+        };
+
+        let tally: std::collections::HashMap<String, usize> = report.fault_tally.iter()
+            .map(|(kind, count)| (format!("{:?}", kind), *count))
+            .collect();
+        let sample_json: Vec<serde_json::Value> = report.sample.iter()
+            .map(|r| serde_json::json!({
+                "fault": format!("{:?}", r.fault),
+                "offset": r.offset,
+                "line": r.line,
+            }))
+            .collect();
+
+        let summary = format!(
+            "Parsed {} line(s), rejected {}\n\nFault tally: {}\n\nSample rejected lines:\n{}",
+            report.parsed_count,
+            report.rejected_count,
+            serde_json::to_string(&tally).unwrap_or_default(),
+            serde_json::to_string(&sample_json).unwrap_or_default()
+        );
+        Ok(CallToolResult::success(vec![Content::text(summary)]))
+    }
+
+    #[tool(description = "Follow a log file for newly appended lines, polling until max_matches is reached or timeout_secs elapses. Handles log rotation (inode/truncation changes) by reopening the file. Useful for live monitoring rather than one-off analysis of a static snapshot. Returns a single batched result once the poll window ends, since this server's tool-call transport is request/response rather than a live stream.")]
+    async fn tail_logs(&self, Parameters(params): Parameters<TailLogsParams>) -> Result<CallToolResult, McpError> {
+        let format = LogFormat::from_str(&params.format);
+        let path = std::path::Path::new(&params.path);
+
+        let status_filter = params.filter_status.as_ref()
+            .and_then(|s| apache_simd::StatusFilter::parse(s));
+
+        let pattern = match params.pattern.as_ref().map(|p| regex::Regex::new(p)) {
+            Some(Ok(re)) => Some(re),
+            Some(Err(e)) => return Ok(CallToolResult::error(vec![Content::text(format!("Invalid regex: {}", e))])),
+            None => None,
+        };
+
+        let is_apache = matches!(format, LogFormat::Apache | LogFormat::Nginx)
+            || (format == LogFormat::Auto && params.path.contains("access"));
+
+        let mut file = match std::fs::File::open(path) {
+            Ok(f) => f,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error opening file: {}", e))])),
+        };
+        let mut meta = match file.metadata() {
+            Ok(m) => m,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("Error reading metadata: {}", e))])),
+        };
+        let mut inode = meta.ino();
+        let mut offset = meta.len();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(params.timeout_secs);
+        let mut matches: Vec<String> = Vec::new();
+        let mut leftover = String::new();
+
+        while matches.len() < params.max_matches as usize && std::time::Instant::now() < deadline {
+            let Ok(current_meta) = std::fs::metadata(path) else {
+                std::thread::sleep(std::time::Duration::from_millis(200));
+                continue;
+            };
+
+            if current_meta.ino() != inode || current_meta.len() < offset {
+                // File was rotated or truncated; reopen and start from the top.
+                file = match std::fs::File::open(path) {
+                    Ok(f) => f,
+                    Err(_) => {
+                        std::thread::sleep(std::time::Duration::from_millis(200));
+                        continue;
+                    }
+                };
+                inode = current_meta.ino();
+                offset = 0;
+                leftover.clear();
+            }
+            meta = current_meta;
+
+            if meta.len() == offset {
+                std::thread::sleep(std::time::Duration::from_millis(200));
+                continue;
+            }
+
+            use std::io::Seek;
+            if file.seek(std::io::SeekFrom::Start(offset)).is_err() {
+                std::thread::sleep(std::time::Duration::from_millis(200));
+                continue;
+            }
+
+            let mut buf = String::new();
+            if file.read_to_string(&mut buf).is_err() {
+                std::thread::sleep(std::time::Duration::from_millis(200));
+                continue;
+            }
+            offset = meta.len();
+
+            leftover.push_str(&buf);
+            let mut lines: Vec<&str> = leftover.split('\n').collect();
+            let tail = lines.pop().unwrap_or("").to_string();
+
+            for line in lines {
+                if line.is_empty() {
+                    continue;
+                }
+                if let Some(filter) = status_filter {
+                    if !is_apache || !extract_apache_status(line).is_some_and(|s| filter.matches(s)) {
+                        continue;
+                    }
+                }
+                if let Some(text) = &params.filter_text {
+                    if !line.contains(text.as_str()) {
+                        continue;
+                    }
+                }
+                if let Some(re) = &pattern {
+                    if !re.is_match(line) {
+                        continue;
+                    }
+                }
+                matches.push(line.to_string());
+                if matches.len() >= params.max_matches as usize {
+                    break;
+                }
+            }
+            leftover = tail;
+        }
+
+        let json = serde_json::to_string(&matches).unwrap_or_default();
+        let summary = format!(
+            "Tailed {} for up to {}s, collected {} matching rows\n\nData:\n{}",
+            params.path, params.timeout_secs, matches.len(), json
+        );
+        Ok(CallToolResult::success(vec![Content::text(summary)]))
+    }
 }
 
 impl ServerHandler for LogForensicsServer {
@@ -632,7 +1490,13 @@ impl ServerHandler for LogForensicsServer {
             instructions: Some(
                 "High-performance log analysis server powered by Polars. Use get_log_schema first \
                  to understand available columns, then use analyze_logs, aggregate_logs, \
-                 search_pattern, or time_analysis to query the data. Supports Apache, Nginx, \
+                 search_pattern, time_analysis, histogram_aggregate, range_aggregate, group_by_regex, \
+                 or query_sql to query the data. Use detect_log_format \
+                 when a file's format isn't obvious from its name or extension. Use detect_spikes to \
+                 surface anomalous time buckets automatically, correlate_sessions to reconstruct \
+                 multi-request client sessions from an Apache/Nginx log, diagnose_log_parsing to tell \
+                 a wrong LogFormat from a handful of corrupt lines, and tail_logs to follow a file for \
+                 newly appended lines instead of analyzing a static snapshot. Supports Apache, Nginx, \
                  Syslog, JSON, and CSV log formats. Can handle files larger than RAM via streaming.".to_string()
             ),
         }